@@ -0,0 +1,82 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use simple_regex::RegexBuilder;
+
+/// The builder as it was before the owned `self -> Self` API: every step
+/// appends to the pattern string and returns a clone of the whole builder.
+#[derive(Clone)]
+struct CloningBuilder {
+    value: String,
+}
+
+impl CloningBuilder {
+    fn new() -> Self {
+        Self { value: String::new() }
+    }
+
+    fn digit(&mut self) -> Self {
+        self.value.push_str("\\d");
+        self.clone()
+    }
+
+    fn group(&mut self, regex: CloningBuilder) -> Self {
+        self.value.push_str("(?:");
+        self.value.push_str(&regex.value);
+        self.value.push(')');
+        self.clone()
+    }
+}
+
+const SIZES: [usize; 3] = [16, 256, 4096];
+
+fn chained(c: &mut Criterion) {
+    let mut group = c.benchmark_group("chained");
+    for n in SIZES {
+        group.bench_with_input(BenchmarkId::new("owned", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut builder = RegexBuilder::new();
+                for _ in 0..n {
+                    builder = builder.digit();
+                }
+                black_box(builder.build())
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("cloning", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut builder = CloningBuilder::new();
+                for _ in 0..n {
+                    builder = builder.digit();
+                }
+                black_box(builder.value)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn nested(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nested");
+    for n in SIZES {
+        group.bench_with_input(BenchmarkId::new("owned", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut builder = RegexBuilder::new().digit();
+                for _ in 0..n {
+                    builder = RegexBuilder::new().digit().group(builder);
+                }
+                black_box(builder.build())
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("cloning", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut builder = CloningBuilder::new().digit();
+                for _ in 0..n {
+                    builder = CloningBuilder::new().digit().group(builder);
+                }
+                black_box(builder.value)
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, chained, nested);
+criterion_main!(benches);
@@ -1,48 +1,38 @@
 use simple_regex::{RegexBuilder, ansi};
 
 fn main() {
-    // \s*(?:\+?[0-9]{10}|\(\d{3}\)|\d{3})(?:[-\s]?\d{3}[-\s]?\d{4})?\s*$
+    // ^\s*(?:\+?[0-9]{10}|(?:\(\d{3}\)|\d{3})[-\s]?\d{3}[-\s]?\d{4})\s*$
     let builder =  RegexBuilder::new()
     .start_of_line()
     .zero_or_more(RegexBuilder::new().whitespace())
-    .group(
+    .any_of([
         RegexBuilder::new()
-            .alternative(
-                RegexBuilder::new()
-                    .alternative(
-                        RegexBuilder::new()
-                            .zero_or_one(RegexBuilder::new().literal('+'))
-                            .exact_repetitions(RegexBuilder::new().range_character_class('0', '9'), 10),
-                        RegexBuilder::new()
-                            .literal('(')
-                            .exact_repetitions(RegexBuilder::new().digit(), 3)
-                            .literal(')'),
-                ),
-                RegexBuilder::new().exact_repetitions(RegexBuilder::new().digit(), 3)
-
-            )
-    )
-    .zero_or_one(
+            .zero_or_one(RegexBuilder::new().literal('+'))
+            .exact_repetitions(RegexBuilder::new().range_character_class('0', '9'), 10),
         RegexBuilder::new()
-            .group(
+            .any_of([
                 RegexBuilder::new()
-                    .zero_or_one(
-                        RegexBuilder::new()
-                            .dash_space_character_class()
-                    )
+                    .literal('(')
                     .exact_repetitions(RegexBuilder::new().digit(), 3)
-                    .zero_or_one(
-                        RegexBuilder::new()
-                            .dash_space_character_class()
-                    )
-                    .exact_repetitions(RegexBuilder::new().digit(), 4)
+                    .literal(')'),
+                RegexBuilder::new().exact_repetitions(RegexBuilder::new().digit(), 3),
+            ])
+            .zero_or_one(
+                RegexBuilder::new()
+                    .dash_space_character_class()
             )
-    )
+            .exact_repetitions(RegexBuilder::new().digit(), 3)
+            .zero_or_one(
+                RegexBuilder::new()
+                    .dash_space_character_class()
+            )
+            .exact_repetitions(RegexBuilder::new().digit(), 4),
+    ])
     .zero_or_more(RegexBuilder::new().whitespace())
     .end_of_line();
 
     let regex_ = builder.to_regex_or_panic();
-    println!("{}", builder.build());
+    println!("{}", builder.to_colored_string());
     println!("{}", ansi::fg_green(
         format!(
             "{}", regex_.is_match("1234567890")
@@ -65,7 +55,7 @@ fn main() {
     ));
     println!("{}", ansi::fg_green(
         format!(
-            "{}", regex_.is_match("1234567890")
+            "{}", regex_.is_match("123")
         )
     ));
 }
\ No newline at end of file
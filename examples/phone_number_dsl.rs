@@ -0,0 +1,22 @@
+use simple_regex::regex;
+
+fn main() {
+    // The pattern of phone_number.rs, written with the `regex!` DSL.
+    let regex = regex![
+        start,
+        ws*,
+        any_of![
+            seq!['+'?, '0'..='9'{10}],
+            seq![
+                any_of![seq!['(', digit{3}, ')'], digit{3}],
+                dash_space?, digit{3}, dash_space?, digit{4},
+            ],
+        ],
+        ws*,
+        end,
+    ];
+    println!("{}", regex.as_str());
+    for number in ["1234567890", "(123) 456-7890", "123-456-7890", "123 456 7890", "123", "12-34"] {
+        println!("{}: {}", number, regex.is_match(number));
+    }
+}
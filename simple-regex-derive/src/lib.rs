@@ -0,0 +1,77 @@
+//! Derive macro for `simple_regex::FromCaptures`.
+//!
+//! This crate is re-exported by `simple-regex` when its `derive` feature is
+//! enabled, and is not meant to be used directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, GenericArgument, LitStr, PathArguments, Type};
+
+/// Implements `FromCaptures` for a struct with named fields.
+///
+/// Each field is read from the capture group of the same name, or from the group
+/// given by `#[group("name")]`, and parsed with `FromStr`. Fields of type
+/// `Option<T>` are `None` when their group did not participate in the match.
+#[proc_macro_derive(FromCaptures, attributes(group))]
+pub fn derive_from_captures(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(&input.ident, "FromCaptures needs a struct with named fields")),
+        },
+        _ => return Err(Error::new_spanned(&input.ident, "FromCaptures can only be derived for structs")),
+    };
+    let mut initializers = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named fields have identifiers");
+        let mut group = ident.to_string();
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("group")) {
+            group = attr.parse_args::<LitStr>()?.value();
+        }
+        let initializer = match optional_inner(&field.ty) {
+            Some(inner) => quote! {
+                ::simple_regex::__private::parse_optional_group::<#inner>(captures, #group)?
+            },
+            None => {
+                let ty = &field.ty;
+                quote! { ::simple_regex::__private::parse_group::<#ty>(captures, #group)? }
+            }
+        };
+        initializers.push(quote! { #ident: #initializer });
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::simple_regex::FromCaptures for #name #ty_generics #where_clause {
+            fn from_captures(
+                captures: &::simple_regex::__private::Captures<'_>,
+            ) -> ::std::result::Result<Self, ::simple_regex::CaptureError> {
+                ::std::result::Result::Ok(#name { #(#initializers),* })
+            }
+        }
+    })
+}
+
+/// Returns `T` if the type is written `Option<T>`.
+fn optional_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => match arguments.args.first()? {
+            GenericArgument::Type(inner) if arguments.args.len() == 1 => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,324 @@
+//! Static facts about the matches of a pattern, computed from its expression tree.
+//!
+//! [`Analysis`] tells how long a match can be, whether the pattern matches the
+//! empty string, whether matches are tied to the start and end of the haystack,
+//! and which characters a match can start with, without running the pattern.
+//! The bounds are safe to filter inputs with: no match is shorter than
+//! [`min_len`](Analysis::min_len), and none starts with a character outside
+//! [`first_chars`](Analysis::first_chars).
+
+use crate::ast::{Anchor, ClassItem, ClassSet, GroupKind, LookKind, Node, Repetition};
+use crate::backtrack::{self, Context};
+use crate::{parse, RegexBuilder};
+
+/// Facts about the matches of a pattern, returned by `RegexBuilder::analyze`.
+///
+/// Lengths are in bytes, as `str::len` counts them.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::RegexBuilder;
+///
+/// let digits = RegexBuilder::new().range_repetitions(RegexBuilder::new().range_character_class('0', '9'), 4, 6);
+///
+/// let analysis = digits.clone().analyze();
+/// assert_eq!((analysis.min_len, analysis.max_len), (4, Some(6)));
+/// assert!(!analysis.nullable);
+/// assert_eq!(analysis.first_chars.unwrap().to_string(), "[0-9]");
+///
+/// // A validator that forgot its anchors also accepts "PIN: 1234!".
+/// assert!(!analysis.anchored_start && !analysis.anchored_end);
+/// let analysis = RegexBuilder::new().start_of_line().then(digits).end_of_line().analyze();
+/// assert!(analysis.anchored_start && analysis.anchored_end);
+///
+/// // Only inputs of 4 to 6 bytes need to be matched.
+/// let inputs = ["123", "1234", "12345678"];
+/// let candidates: Vec<&str> = inputs
+///     .into_iter()
+///     .filter(|input| input.len() >= analysis.min_len && analysis.max_len.is_none_or(|max| input.len() <= max))
+///     .collect();
+/// assert_eq!(candidates, ["1234"]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Analysis {
+    /// The length of the shortest match.
+    pub min_len: usize,
+    /// The length of the longest match, or `None` if matches can be arbitrarily long.
+    pub max_len: Option<usize>,
+    /// Whether the pattern matches the empty string `""`.
+    pub nullable: bool,
+    /// Whether every match starts at the start of the haystack.
+    pub anchored_start: bool,
+    /// Whether every match ends at the end of the haystack.
+    pub anchored_end: bool,
+    /// The characters a non-empty match can start with, or `None` if they are not known,
+    /// as after a backreference to a later group.
+    pub first_chars: Option<ClassSet>,
+}
+
+impl Analysis {
+    /// Analyzes the pattern of the given builder.
+    pub fn new(builder: &RegexBuilder) -> Self {
+        let names = parse::group_names(builder.nodes());
+        let mut analyzer = Analyzer {
+            names: &names,
+            infos: vec![None; names.len()],
+            next: 0,
+        };
+        match analyzer.concat(builder.nodes(), Context::default()) {
+            Some(info) => Analysis {
+                min_len: info.min,
+                max_len: info.max,
+                nullable: info.nullable,
+                anchored_start: info.start,
+                anchored_end: info.end,
+                first_chars: info.first.map(|ranges| class_set(&ranges)),
+            },
+            // A pattern that never matches has no matches to describe.
+            None => Analysis {
+                min_len: 0,
+                max_len: Some(0),
+                nullable: false,
+                anchored_start: false,
+                anchored_end: false,
+                first_chars: Some(class_set(&[])),
+            },
+        }
+    }
+}
+
+/// What is known about the matches of a node.
+#[derive(Clone, Debug)]
+struct Info {
+    min: usize,
+    max: Option<usize>,
+    /// Whether the node matches the empty haystack.
+    nullable: bool,
+    start: bool,
+    end: bool,
+    /// The characters a non-empty match can start with, sorted and merged.
+    first: Option<Vec<(char, char)>>,
+}
+
+impl Info {
+    fn empty() -> Info {
+        Info {
+            min: 0,
+            max: Some(0),
+            nullable: true,
+            start: false,
+            end: false,
+            first: Some(Vec::new()),
+        }
+    }
+
+    fn unknown() -> Info {
+        Info {
+            min: 0,
+            max: None,
+            nullable: false,
+            start: false,
+            end: false,
+            first: None,
+        }
+    }
+}
+
+struct Analyzer<'a> {
+    /// The names of the capturing groups, as returned by `parse::group_names`.
+    names: &'a [Option<String>],
+    /// The information about each capturing group analyzed so far.
+    infos: Vec<Option<(Info, bool)>>,
+    /// The index in `infos` of the next capturing group.
+    next: usize,
+}
+
+impl Analyzer<'_> {
+    /// Returns what is known about the matches of `node`, or `None` if it never matches.
+    fn info(&mut self, node: &Node, context: Context) -> Option<Info> {
+        Some(match node {
+            Node::Empty => Info::empty(),
+            Node::Literal(c) => self.class(&Node::Literal(*c).to_string(), context)?,
+            Node::Text(text) => {
+                let nodes: Vec<Node> = text.chars().map(Node::Literal).collect();
+                self.concat(&nodes, context)?
+            }
+            Node::Dot | Node::Class(_) => self.class(&node.to_string(), context)?,
+            Node::Raw(text) => match parse::parse_raw(text) {
+                Some(node) => self.info(&node, context)?,
+                // Text the parser leaves raw, such as `\p{Greek}`, is usually a class.
+                None => self.class(text, context).unwrap_or_else(Info::unknown),
+            },
+            Node::Anchor(anchor) => {
+                let text = !context.multi_line;
+                Info {
+                    // The empty haystack has no word characters, so no word boundary.
+                    nullable: *anchor != Anchor::WordBoundary,
+                    start: matches!(anchor, Anchor::StartOfText) || (text && *anchor == Anchor::StartOfLine),
+                    end: matches!(anchor, Anchor::EndOfText) || (text && *anchor == Anchor::EndOfLine),
+                    ..Info::empty()
+                }
+            }
+            Node::Look { kind, node } => {
+                let inner = self.info(node, context);
+                let positive = matches!(kind, LookKind::PositiveLookahead | LookKind::PositiveLookbehind);
+                if positive && inner.is_none() {
+                    return None;
+                }
+                let inner_nullable = inner.as_ref().is_some_and(|inner| inner.nullable);
+                Info {
+                    // Against the empty haystack, the assertion only sees the empty string.
+                    nullable: inner_nullable == positive,
+                    start: *kind == LookKind::PositiveLookahead && inner.as_ref().is_some_and(|inner| inner.start),
+                    end: *kind == LookKind::PositiveLookbehind && inner.as_ref().is_some_and(|inner| inner.end),
+                    ..Info::empty()
+                }
+            }
+            Node::Repeat { node, repetition, .. } => {
+                let (min, max) = match *repetition {
+                    Repetition::ZeroOrMore => (0, None),
+                    Repetition::OneOrMore => (1, None),
+                    Repetition::ZeroOrOne => (0, Some(1)),
+                    Repetition::Exactly(n) => (n, Some(n)),
+                    Repetition::AtLeast(n) => (n, None),
+                    Repetition::Between(n, m) => (n, Some(m)),
+                };
+                let Some(inner) = self.info(node, context) else {
+                    return (min == 0).then(Info::empty);
+                };
+                if max == Some(0) {
+                    return Some(Info::empty());
+                }
+                let max = match (inner.max, max) {
+                    (Some(0), _) => Some(0),
+                    (Some(len), Some(max)) => len.checked_mul(max),
+                    _ => None,
+                };
+                Info {
+                    min: inner.min.saturating_mul(min),
+                    max,
+                    nullable: min == 0 || inner.nullable,
+                    start: min > 0 && inner.start,
+                    end: min > 0 && inner.end,
+                    first: inner.first,
+                }
+            }
+            Node::Group { kind, node: inner } => {
+                if let GroupKind::NonCapturing = kind {
+                    return self.info(inner, context);
+                }
+                let index = self.next;
+                self.next += 1;
+                let info = self.info(inner, context);
+                self.infos[index] = info.clone().map(|info| (info, context.case_insensitive));
+                info?
+            }
+            Node::Flags { flags, node } => {
+                let mut context = context;
+                flags.enabled.iter().for_each(|flag| context.set(*flag, true));
+                flags.disabled.iter().for_each(|flag| context.set(*flag, false));
+                self.info(node, context)?
+            }
+            Node::Backreference(group) => self.backreference(group.checked_sub(1), context),
+            Node::NamedBackreference(name) => {
+                let index = self.names.iter().position(|group| group.as_deref() == Some(name));
+                self.backreference(index, context)
+            }
+            Node::Alternation(branches) => {
+                let infos: Vec<Info> = branches.iter().filter_map(|branch| self.info(branch, context)).collect();
+                let (first, rest) = infos.split_first()?;
+                let mut info = first.clone();
+                for branch in rest {
+                    info.min = info.min.min(branch.min);
+                    info.max = info.max.zip(branch.max).map(|(a, b)| a.max(b));
+                    info.nullable |= branch.nullable;
+                    info.start &= branch.start;
+                    info.end &= branch.end;
+                    info.first = union(info.first, branch.first.as_deref());
+                }
+                info
+            }
+            Node::Concat(nodes) => self.concat(nodes, context)?,
+        })
+    }
+
+    fn concat(&mut self, nodes: &[Node], context: Context) -> Option<Info> {
+        let mut info = Info::empty();
+        // Whether every node so far can match without consuming a character.
+        let mut empty = true;
+        for node in nodes {
+            let next = self.info(node, context)?;
+            if empty {
+                info.first = union(info.first, next.first.as_deref());
+                empty = next.min == 0;
+            }
+            info.min = info.min.saturating_add(next.min);
+            info.max = info.max.zip(next.max).and_then(|(a, b)| a.checked_add(b));
+            info.nullable &= next.nullable;
+            // Once a node is anchored, every node around it has to meet the anchor.
+            info.start |= next.start;
+            info.end |= next.end;
+        }
+        Some(info)
+    }
+
+    fn class(&mut self, syntax: &str, context: Context) -> Option<Info> {
+        let ranges = backtrack::class_ranges(&context.scope(syntax)).ok()?;
+        let min = ranges.iter().map(|(start, _)| start.len_utf8()).min()?;
+        let max = ranges.iter().map(|(_, end)| end.len_utf8()).max()?;
+        Some(Info {
+            min,
+            max: Some(max),
+            nullable: false,
+            start: false,
+            end: false,
+            first: union(Some(Vec::new()), Some(&ranges)),
+        })
+    }
+
+    fn backreference(&self, index: Option<usize>, context: Context) -> Info {
+        match index.and_then(|index| self.infos.get(index)) {
+            Some(Some((group, case_insensitive))) => {
+                // Case folding can change the text, and its length in bytes.
+                if context.case_insensitive || *case_insensitive {
+                    Info {
+                        nullable: group.nullable,
+                        ..Info::unknown()
+                    }
+                } else {
+                    Info {
+                        start: false,
+                        end: false,
+                        ..group.clone()
+                    }
+                }
+            }
+            // A group that comes later, or that is not defined.
+            _ => Info::unknown(),
+        }
+    }
+}
+
+/// Returns the union of two sets of ranges, where `None` stands for any character.
+fn union(a: Option<Vec<(char, char)>>, b: Option<&[(char, char)]>) -> Option<Vec<(char, char)>> {
+    let mut ranges = a?;
+    ranges.extend_from_slice(b?);
+    ranges.sort_unstable();
+    let mut merged: Vec<(char, char)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start as u32 <= last.1 as u32 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    Some(merged)
+}
+
+fn class_set(ranges: &[(char, char)]) -> ClassSet {
+    let items = ranges
+        .iter()
+        .map(|&(start, end)| if start == end { ClassItem::Char(start) } else { ClassItem::Range(start, end) })
+        .collect();
+    ClassSet { negated: false, items }
+}
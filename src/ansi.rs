@@ -0,0 +1,379 @@
+//! ANSI escape codes for coloring terminal output.
+//!
+//! A [`Style`] combines a foreground color, a background color and text
+//! attributes, and [`Style::paint`] wraps a value in a [`Painted`] that writes
+//! it between the escape codes when displayed, without allocating.
+//!
+//! Styled output honors the [`ColorChoice`] set with [`set_color_choice`]. By
+//! default ([`ColorChoice::Auto`]), escape codes are left out when the
+//! `NO_COLOR` environment variable is set or standard output is not a
+//! terminal. The `fg_*` functions predate `Style` and always emit escape codes.
+//!
+//! # Example
+//!
+//! ```
+//! use simple_regex::ansi::{self, Color, ColorChoice, Style};
+//!
+//! ansi::set_color_choice(ColorChoice::Always);
+//! let warning = Style::new().fg(Color::Yellow).bg(Color::Fixed(236)).bold();
+//! assert_eq!(warning.paint("careful").to_string(), "\x1b[1;33;48;5;236mcareful\x1b[0m");
+//!
+//! ansi::set_color_choice(ColorChoice::Never);
+//! assert_eq!(warning.paint("careful").to_string(), "careful");
+//! ```
+
+use std::fmt;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+/// A terminal color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Purple,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightPurple,
+    BrightCyan,
+    BrightWhite,
+    /// A color of the 256-color palette.
+    Fixed(u8),
+    /// A 24-bit color.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// Wraps `value` so that it is displayed in this foreground color.
+    pub fn paint<T: fmt::Display>(self, value: T) -> Painted<T> {
+        Style::new().fg(self).paint(value)
+    }
+
+    /// Writes the parameters selecting this color, with `base` being 30 for
+    /// the foreground and 40 for the background.
+    fn write_code(self, f: &mut fmt::Formatter<'_>, base: u8) -> fmt::Result {
+        match self {
+            Color::Fixed(n) => write!(f, "{};5;{}", base + 8, n),
+            Color::Rgb(r, g, b) => write!(f, "{};2;{};{};{}", base + 8, r, g, b),
+            color => {
+                let index = color.index();
+                if index < 8 {
+                    write!(f, "{}", base + index)
+                } else {
+                    write!(f, "{}", base + 60 + index - 8)
+                }
+            }
+        }
+    }
+
+    /// Returns the index of a named color, the bright ones following the others.
+    fn index(self) -> u8 {
+        match self {
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Purple => 5,
+            Color::Cyan => 6,
+            Color::White => 7,
+            Color::BrightBlack => 8,
+            Color::BrightRed => 9,
+            Color::BrightGreen => 10,
+            Color::BrightYellow => 11,
+            Color::BrightBlue => 12,
+            Color::BrightPurple => 13,
+            Color::BrightCyan => 14,
+            Color::BrightWhite => 15,
+            Color::Fixed(_) | Color::Rgb(..) => unreachable!(),
+        }
+    }
+}
+
+const BOLD: u8 = 1 << 0;
+const DIM: u8 = 1 << 1;
+const ITALIC: u8 = 1 << 2;
+const UNDERLINE: u8 = 1 << 3;
+const BLINK: u8 = 1 << 4;
+const REVERSE: u8 = 1 << 5;
+const HIDDEN: u8 = 1 << 6;
+const STRIKETHROUGH: u8 = 1 << 7;
+
+/// The SGR parameter of each attribute, in the order of their bits.
+const ATTRIBUTE_CODES: [u8; 8] = [1, 2, 3, 4, 5, 7, 8, 9];
+
+/// A combination of foreground color, background color and text attributes.
+///
+/// Styles are `Copy` and built with chained `const` methods, so they can be
+/// declared as constants.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::ansi::{self, Color, ColorChoice, Style};
+///
+/// const HEADING: Style = Style::new().fg(Color::Rgb(255, 128, 0)).underline();
+///
+/// ansi::set_color_choice(ColorChoice::Always);
+/// assert_eq!(format!("{}", HEADING.paint("Title")), "\x1b[4;38;2;255;128;0mTitle\x1b[0m");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    attributes: u8,
+}
+
+impl Style {
+    /// Creates a style that changes nothing.
+    pub const fn new() -> Self {
+        Style {
+            fg: None,
+            bg: None,
+            attributes: 0,
+        }
+    }
+
+    /// Sets the foreground color.
+    pub const fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    /// Sets the background color.
+    pub const fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    const fn attribute(mut self, attribute: u8) -> Self {
+        self.attributes |= attribute;
+        self
+    }
+
+    /// Makes the text bold.
+    pub const fn bold(self) -> Self {
+        self.attribute(BOLD)
+    }
+
+    /// Makes the text dim.
+    pub const fn dim(self) -> Self {
+        self.attribute(DIM)
+    }
+
+    /// Makes the text italic.
+    pub const fn italic(self) -> Self {
+        self.attribute(ITALIC)
+    }
+
+    /// Underlines the text.
+    pub const fn underline(self) -> Self {
+        self.attribute(UNDERLINE)
+    }
+
+    /// Makes the text blink.
+    pub const fn blink(self) -> Self {
+        self.attribute(BLINK)
+    }
+
+    /// Swaps the foreground and background colors.
+    pub const fn reverse(self) -> Self {
+        self.attribute(REVERSE)
+    }
+
+    /// Hides the text.
+    pub const fn hidden(self) -> Self {
+        self.attribute(HIDDEN)
+    }
+
+    /// Strikes the text through.
+    pub const fn strikethrough(self) -> Self {
+        self.attribute(STRIKETHROUGH)
+    }
+
+    /// Returns `true` if the style changes nothing.
+    pub const fn is_plain(&self) -> bool {
+        self.fg.is_none() && self.bg.is_none() && self.attributes == 0
+    }
+
+    /// Wraps `value` so that it is displayed in this style.
+    pub fn paint<T: fmt::Display>(self, value: T) -> Painted<T> {
+        Painted { style: self, value }
+    }
+
+    fn write_prefix(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("\x1b[")?;
+        let mut first = true;
+        let mut separate = |f: &mut fmt::Formatter<'_>| {
+            let separator = if first { "" } else { ";" };
+            first = false;
+            f.write_str(separator)
+        };
+        for (bit, code) in ATTRIBUTE_CODES.iter().enumerate() {
+            if self.attributes & (1 << bit) != 0 {
+                separate(f)?;
+                write!(f, "{}", code)?;
+            }
+        }
+        if let Some(fg) = self.fg {
+            separate(f)?;
+            fg.write_code(f, 30)?;
+        }
+        if let Some(bg) = self.bg {
+            separate(f)?;
+            bg.write_code(f, 40)?;
+        }
+        f.write_str("m")
+    }
+}
+
+/// A value displayed in a [`Style`], created by [`Style::paint`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Painted<T> {
+    style: Style,
+    value: T,
+}
+
+impl<T: fmt::Display> fmt::Display for Painted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.style.is_plain() || !colors_enabled() {
+            return self.value.fmt(f);
+        }
+        self.style.write_prefix(f)?;
+        self.value.fmt(f)?;
+        f.write_str(ANSI_RESET)
+    }
+}
+
+/// Whether styled output includes escape codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Include escape codes unless `NO_COLOR` is set or standard output is not a terminal.
+    Auto,
+    /// Always include escape codes.
+    Always,
+    /// Never include escape codes.
+    Never,
+}
+
+static COLOR_CHOICE: AtomicU8 = AtomicU8::new(ColorChoice::Auto as u8);
+
+/// Sets whether styled output includes escape codes, for the whole program.
+pub fn set_color_choice(choice: ColorChoice) {
+    COLOR_CHOICE.store(choice as u8, Ordering::Relaxed);
+}
+
+/// Returns `true` if styled output currently includes escape codes.
+pub fn colors_enabled() -> bool {
+    static DETECTED: OnceLock<bool> = OnceLock::new();
+    match COLOR_CHOICE.load(Ordering::Relaxed) {
+        choice if choice == ColorChoice::Always as u8 => true,
+        choice if choice == ColorChoice::Never as u8 => false,
+        _ => *DETECTED.get_or_init(|| {
+            let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+            !no_color && std::io::stdout().is_terminal()
+        }),
+    }
+}
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Formats the given text with black foreground color, following the current
+/// [`ColorChoice`] like [`Color::Black`]'s `paint`.
+pub fn fg_black(text: impl fmt::Display) -> String {
+    Color::Black.paint(text).to_string()
+}
+
+/// Formats the given text with red foreground color, following the current
+/// [`ColorChoice`] like [`Color::Red`]'s `paint`.
+pub fn fg_red(text: impl fmt::Display) -> String {
+    Color::Red.paint(text).to_string()
+}
+
+/// Formats the given text with green foreground color, following the current
+/// [`ColorChoice`] like [`Color::Green`]'s `paint`.
+pub fn fg_green(text: impl fmt::Display) -> String {
+    Color::Green.paint(text).to_string()
+}
+
+/// Formats the given text with yellow foreground color, following the current
+/// [`ColorChoice`] like [`Color::Yellow`]'s `paint`.
+pub fn fg_yellow(text: impl fmt::Display) -> String {
+    Color::Yellow.paint(text).to_string()
+}
+
+/// Formats the given text with blue foreground color, following the current
+/// [`ColorChoice`] like [`Color::Blue`]'s `paint`.
+pub fn fg_blue(text: impl fmt::Display) -> String {
+    Color::Blue.paint(text).to_string()
+}
+
+/// Formats the given text with purple foreground color, following the current
+/// [`ColorChoice`] like [`Color::Purple`]'s `paint`.
+pub fn fg_purple(text: impl fmt::Display) -> String {
+    Color::Purple.paint(text).to_string()
+}
+
+/// Formats the given text with cyan foreground color, following the current
+/// [`ColorChoice`] like [`Color::Cyan`]'s `paint`.
+pub fn fg_cyan(text: impl fmt::Display) -> String {
+    Color::Cyan.paint(text).to_string()
+}
+
+/// Formats the given text with white foreground color, following the current
+/// [`ColorChoice`] like [`Color::White`]'s `paint`.
+pub fn fg_white(text: impl fmt::Display) -> String {
+    Color::White.paint(text).to_string()
+}
+
+/// Colors every match of `regex` in `haystack`. Each capture group gets a color of
+/// its own, and text matched by nested groups takes the color of the innermost one.
+/// The colors follow the current [`ColorChoice`].
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{ansi, RegexBuilder};
+///
+/// let regex = RegexBuilder::new()
+///     .capturing_group(RegexBuilder::new().one_or_more(RegexBuilder::new().digit()))
+///     .string("px")
+///     .to_regex()
+///     .unwrap();
+/// ansi::set_color_choice(ansi::ColorChoice::Always);
+/// let highlighted = ansi::highlight_matches(&regex, "width: 12px");
+/// assert_eq!(highlighted, "width: \x1b[32m12\x1b[0m\x1b[31mpx\x1b[0m");
+/// println!("{}\n{}", highlighted, ansi::match_legend(&regex));
+/// ```
+pub fn highlight_matches(regex: &regex::Regex, haystack: &str) -> String {
+    crate::highlight::matches(regex, haystack)
+}
+
+/// Lists the capture groups of `regex` in the colors used by [`highlight_matches`],
+/// labelled by name where they have one, and by index otherwise. The whole match
+/// is labelled `match`.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{ansi, RegexBuilder};
+///
+/// let regex = RegexBuilder::parse(r"(?P<key>\w+)=(\w+)").unwrap().to_regex().unwrap();
+/// ansi::set_color_choice(ansi::ColorChoice::Always);
+/// assert_eq!(
+///     ansi::match_legend(&regex),
+///     "\x1b[31mmatch\x1b[0m \x1b[32mkey\x1b[0m \x1b[33m2\x1b[0m"
+/// );
+/// ```
+pub fn match_legend(regex: &regex::Regex) -> String {
+    crate::highlight::legend(regex)
+}
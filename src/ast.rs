@@ -0,0 +1,776 @@
+//! Typed expression tree produced by `RegexBuilder`.
+//!
+//! Every builder method appends a [`Node`] instead of raw pattern text, and the
+//! pattern string is only rendered when it is asked for (through `Display`).
+//! This keeps the structure of the built expression available for inspection
+//! and transformation.
+
+use std::fmt::{self, Write};
+use std::ops::Range;
+
+/// A single node of a regular expression tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Node {
+    /// Matches the empty string.
+    Empty,
+    /// Pattern text that is inserted verbatim.
+    Raw(String),
+    /// A single literal character.
+    Literal(char),
+    /// A literal string, escaped when rendered.
+    Text(String),
+    /// Any single character (`.`).
+    Dot,
+    /// A zero-width anchor.
+    Anchor(Anchor),
+    /// A character class.
+    Class(Class),
+    /// A repeated sub-expression, lazy (`*?`) unless `greedy` is set.
+    Repeat {
+        node: Box<Node>,
+        repetition: Repetition,
+        greedy: bool,
+    },
+    /// A grouped sub-expression.
+    Group {
+        kind: GroupKind,
+        node: Box<Node>,
+    },
+    /// A sub-expression with inline flags applied, `(?i:...)`.
+    Flags {
+        flags: Flags,
+        node: Box<Node>,
+    },
+    /// A lookahead or lookbehind assertion.
+    Look {
+        kind: LookKind,
+        node: Box<Node>,
+    },
+    /// A backreference to a numbered capturing group.
+    Backreference(usize),
+    /// A backreference to a named capturing group, `\k<name>`.
+    NamedBackreference(String),
+    /// Any one of the given branches.
+    Alternation(Vec<Node>),
+    /// The given nodes, one after another.
+    Concat(Vec<Node>),
+}
+
+/// Zero-width anchors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    /// `^`
+    StartOfLine,
+    /// `$`
+    EndOfLine,
+    /// `\A`
+    StartOfText,
+    /// `\z`
+    EndOfText,
+    /// `\b`
+    WordBoundary,
+    /// `\B`
+    NonWordBoundary,
+}
+
+/// Quantifiers applied by [`Node::Repeat`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Repetition {
+    /// `*`
+    ZeroOrMore,
+    /// `+`
+    OneOrMore,
+    /// `?`
+    ZeroOrOne,
+    /// `{n}`
+    Exactly(usize),
+    /// `{n,}`
+    AtLeast(usize),
+    /// `{n,m}`
+    Between(usize, usize),
+}
+
+/// Kinds of [`Node::Group`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GroupKind {
+    /// `(...)`
+    Capturing,
+    /// `(?:...)`
+    NonCapturing,
+    /// `(?P<name>...)`
+    Named(String),
+}
+
+/// Inline flags that can be set or cleared by [`Node::Flags`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Flag {
+    /// `i`: letters match both upper and lower case.
+    CaseInsensitive,
+    /// `m`: `^` and `$` match at the start and end of each line.
+    MultiLine,
+    /// `s`: `.` also matches `\n`.
+    DotMatchesNewLine,
+    /// `x`: whitespace is ignored and `#` starts a comment.
+    IgnoreWhitespace,
+    /// `U`: swaps the meaning of greedy and lazy quantifiers.
+    SwapGreed,
+    /// `R`: `\r\n` is treated as a line terminator.
+    Crlf,
+}
+
+/// Flags set and cleared by a [`Node::Flags`] group, `(?is-m:...)`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Flags {
+    pub enabled: Vec<Flag>,
+    pub disabled: Vec<Flag>,
+}
+
+/// Kinds of [`Node::Look`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LookKind {
+    /// `(?=...)`
+    PositiveLookahead,
+    /// `(?!...)`
+    NegativeLookahead,
+    /// `(?<=...)`
+    PositiveLookbehind,
+    /// `(?<!...)`
+    NegativeLookbehind,
+}
+
+/// Character classes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Class {
+    /// A Perl class such as `\d`.
+    Perl(PerlClass),
+    /// A bracketed class such as `[a-z]`.
+    Set(ClassSet),
+}
+
+/// Perl character classes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PerlClass {
+    /// `\d`
+    Digit,
+    /// `\D`
+    NonDigit,
+    /// `\w`
+    Word,
+    /// `\W`
+    NonWord,
+    /// `\s`
+    Whitespace,
+    /// `\S`
+    NonWhitespace,
+}
+
+/// A bracketed character class.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClassSet {
+    pub negated: bool,
+    pub items: Vec<ClassItem>,
+}
+
+/// A single item inside a bracketed character class.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClassItem {
+    /// A single character.
+    Char(char),
+    /// An inclusive range of characters, `a-z`.
+    Range(char, char),
+    /// A Perl class, `\d`.
+    Perl(PerlClass),
+    /// A named ASCII class, `[:alpha:]`.
+    Ascii(String),
+}
+
+impl Node {
+    /// Turns a list of nodes into a single node, avoiding needless nesting.
+    pub fn concat(mut nodes: Vec<Node>) -> Node {
+        match nodes.len() {
+            0 => Node::Empty,
+            1 => nodes.pop().unwrap(),
+            _ => Node::Concat(nodes),
+        }
+    }
+
+    /// Calls `visit` on this node and then on every node below it, depth first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let node = RegexBuilder::new()
+    ///     .zero_or_more(RegexBuilder::new().digit().dot())
+    ///     .into_node();
+    /// let mut count = 0;
+    /// node.walk(&mut |_| count += 1);
+    /// assert_eq!(count, 4);
+    /// ```
+    pub fn walk<'a>(&'a self, visit: &mut impl FnMut(&'a Node)) {
+        visit(self);
+        match self {
+            Node::Repeat { node, .. }
+            | Node::Group { node, .. }
+            | Node::Flags { node, .. }
+            | Node::Look { node, .. } => node.walk(visit),
+            Node::Alternation(nodes) | Node::Concat(nodes) => nodes.iter().for_each(|node| node.walk(visit)),
+            _ => {}
+        }
+    }
+
+    /// Builds an alternation of the given branches, flattening branches that
+    /// are alternations themselves.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::ast::Node;
+    ///
+    /// let inner = Node::alternation(vec![Node::Dot, Node::Empty]);
+    /// let node = Node::alternation(vec![inner, Node::Raw("a".to_string())]);
+    /// assert_eq!(node, Node::Alternation(vec![Node::Dot, Node::Empty, Node::Raw("a".to_string())]));
+    /// ```
+    pub fn alternation(branches: Vec<Node>) -> Node {
+        let mut flat = Vec::with_capacity(branches.len());
+        for branch in branches {
+            match branch {
+                Node::Alternation(inner) => flat.extend(inner),
+                branch => flat.push(branch),
+            }
+        }
+        if flat.len() == 1 {
+            flat.pop().unwrap()
+        } else {
+            Node::Alternation(flat)
+        }
+    }
+
+    /// Returns the name of the `RegexBuilder` method that appends this node,
+    /// or `None` for sequences of nodes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let node = RegexBuilder::new().optional(RegexBuilder::new().digit()).into_node();
+    /// assert_eq!(node.method(), Some("optional"));
+    /// ```
+    pub fn method(&self) -> Option<&'static str> {
+        let method = match self {
+            Node::Empty | Node::Concat(_) => return None,
+            Node::Raw(_) => "raw",
+            Node::Text(_) => "string",
+            Node::Literal(_) => "literal",
+            Node::Dot => "dot",
+            Node::Anchor(Anchor::StartOfLine) => "start_of_line",
+            Node::Anchor(Anchor::EndOfLine) => "end_of_line",
+            Node::Anchor(Anchor::StartOfText | Anchor::EndOfText) => "escape",
+            Node::Anchor(Anchor::WordBoundary) => "word_boundary",
+            Node::Anchor(Anchor::NonWordBoundary) => "non_word_boundary",
+            Node::Class(Class::Perl(class)) => match class {
+                PerlClass::Digit => "digit",
+                PerlClass::NonDigit => "non_digit",
+                PerlClass::Word => "word_character",
+                PerlClass::NonWord => "non_word_character",
+                PerlClass::Whitespace => "whitespace",
+                PerlClass::NonWhitespace => "non_whitespace",
+            },
+            Node::Class(Class::Set(set)) => match (set.negated, set.items.as_slice()) {
+                (true, _) => "negated_character_class",
+                (false, [ClassItem::Range(_, _)]) => "range_character_class",
+                (false, [ClassItem::Char('-'), ClassItem::Perl(PerlClass::Whitespace)]) => "dash_space_character_class",
+                (false, _) => "character_class",
+            },
+            Node::Repeat { greedy: false, .. } => "lazy",
+            Node::Repeat { node, repetition, .. } => match (repetition, node.as_ref()) {
+                (Repetition::ZeroOrOne, Node::Group { kind: GroupKind::Capturing, .. }) => "optional",
+                (Repetition::ZeroOrMore, _) => "zero_or_more",
+                (Repetition::OneOrMore, _) => "one_or_more",
+                (Repetition::ZeroOrOne, _) => "zero_or_one",
+                (Repetition::Exactly(_), _) => "exact_repetitions",
+                (Repetition::AtLeast(_), _) => "min_repetitions",
+                (Repetition::Between(_, _), _) => "range_repetitions",
+            },
+            Node::Group { kind: GroupKind::Capturing, .. } => "capturing_group",
+            Node::Group { kind: GroupKind::Named(_), .. } => "named_group",
+            Node::Group { kind: GroupKind::NonCapturing, .. } => "group",
+            Node::Flags { flags, .. } => match (flags.enabled.as_slice(), flags.disabled.is_empty()) {
+                ([Flag::CaseInsensitive], true) => "case_insensitive",
+                ([Flag::MultiLine], true) => "multiline",
+                ([Flag::DotMatchesNewLine], true) => "dot_all",
+                ([Flag::IgnoreWhitespace], true) => "ignore_whitespace",
+                ([Flag::SwapGreed], true) => "swap_greed",
+                ([Flag::Crlf], true) => "crlf",
+                _ => "with_flags",
+            },
+            Node::Look { kind, .. } => match kind {
+                LookKind::PositiveLookahead => "positive_lookahead",
+                LookKind::NegativeLookahead => "negative_lookahead",
+                LookKind::PositiveLookbehind => "positive_lookbehind",
+                LookKind::NegativeLookbehind => "negative_lookbehind",
+            },
+            Node::Backreference(_) => "backreference",
+            Node::NamedBackreference(_) => "backreference_to",
+            Node::Alternation(_) => "any_of",
+        };
+        Some(method)
+    }
+
+    /// Describes the arguments passed to [`Node::method`], with nested
+    /// builders shown as the pattern they render to, between slashes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let node = RegexBuilder::new()
+    ///     .exact_repetitions(RegexBuilder::new().character_class("ab"), 3)
+    ///     .into_node();
+    /// assert_eq!(node.arguments(), "/[ab]/, 3");
+    /// ```
+    pub fn arguments(&self) -> String {
+        fn pattern(node: &Node) -> String {
+            format!("/{}/", render(std::slice::from_ref(node), None))
+        }
+        match self {
+            Node::Empty | Node::Dot | Node::Concat(_) => String::new(),
+            Node::Raw(text) | Node::Text(text) => format!("{:?}", text),
+            Node::Literal(c) => format!("{:?}", c),
+            Node::Anchor(Anchor::StartOfText) => "'A'".to_string(),
+            Node::Anchor(Anchor::EndOfText) => "'z'".to_string(),
+            Node::Anchor(_) | Node::Class(Class::Perl(_)) => String::new(),
+            Node::Class(Class::Set(set)) => match (set.negated, set.items.as_slice()) {
+                (false, [ClassItem::Range(start, end)]) => format!("{:?}, {:?}", start, end),
+                (false, [ClassItem::Char('-'), ClassItem::Perl(PerlClass::Whitespace)]) => String::new(),
+                _ => format!("{:?}", set.body()),
+            },
+            Node::Repeat { node, repetition, greedy: false } => pattern(&Node::Repeat {
+                node: node.clone(),
+                repetition: *repetition,
+                greedy: true,
+            }),
+            Node::Repeat { node, repetition, .. } => {
+                let node = match (repetition, node.as_ref()) {
+                    (Repetition::ZeroOrOne, Node::Group { kind: GroupKind::Capturing, node }) => node,
+                    _ => node,
+                };
+                match repetition {
+                    Repetition::Exactly(n) | Repetition::AtLeast(n) => format!("{}, {}", pattern(node), n),
+                    Repetition::Between(n, m) => format!("{}, {}, {}", pattern(node), n, m),
+                    _ => pattern(node),
+                }
+            }
+            Node::Flags { flags, node } if self.method() == Some("with_flags") => {
+                let flags: Vec<String> = flags.enabled.iter().map(|flag| format!("Flag::{:?}", flag)).collect();
+                format!("[{}], {}", flags.join(", "), pattern(node))
+            }
+            Node::Group { kind: GroupKind::Named(name), node } => format!("{:?}, {}", name, pattern(node)),
+            Node::Group { node, .. } | Node::Flags { node, .. } | Node::Look { node, .. } => pattern(node),
+            Node::Backreference(n) => n.to_string(),
+            Node::NamedBackreference(name) => format!("&{}", name),
+            Node::Alternation(branches) => {
+                let branches: Vec<String> = branches.iter().map(pattern).collect();
+                format!("[{}]", branches.join(", "))
+            }
+        }
+    }
+
+    /// Returns `true` if the node renders as a single atom, so that a
+    /// quantifier placed after it applies to the whole node.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::ast::Node;
+    ///
+    /// assert!(Node::Dot.is_atom());
+    /// assert!(Node::Raw("\\d".to_string()).is_atom());
+    /// assert!(!Node::Raw("ab".to_string()).is_atom());
+    /// ```
+    pub fn is_atom(&self) -> bool {
+        match self {
+            Node::Literal(_)
+            | Node::Dot
+            | Node::Anchor(_)
+            | Node::Class(_)
+            | Node::Group { .. }
+            | Node::Flags { .. }
+            | Node::Look { .. }
+            | Node::Backreference(_)
+            | Node::NamedBackreference(_)
+            | Node::Alternation(_) => true,
+            Node::Text(text) => text.chars().count() == 1,
+            Node::Raw(text) => {
+                let mut chars = text.chars();
+                match (chars.next(), chars.next(), chars.next()) {
+                    (Some('\\'), Some(_), None) => true,
+                    (Some(c), None, None) => !"\\|()[]{}*+?".contains(c),
+                    _ => false,
+                }
+            }
+            Node::Empty | Node::Repeat { .. } | Node::Concat(_) => false,
+        }
+    }
+}
+
+impl ClassSet {
+    /// Returns the class items in the form accepted by `character_class`,
+    /// without brackets or negation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::ast::ClassSet;
+    ///
+    /// assert_eq!(ClassSet::parse(true, "a-z_-").body(), "a-z_-");
+    /// ```
+    pub fn body(&self) -> String {
+        let mut body = String::new();
+        self.write_body(&mut body).expect("writing to a String cannot fail");
+        body
+    }
+
+    fn write_body<W: Write + ?Sized>(&self, f: &mut W) -> fmt::Result {
+        let last = self.items.len().saturating_sub(1);
+        for (i, item) in self.items.iter().enumerate() {
+            match item {
+                // A dash is only literal at either end of the class.
+                ClassItem::Char('-') if i == 0 || i == last => f.write_str("-")?,
+                ClassItem::Char(c) => write_class_char(f, *c)?,
+                ClassItem::Range(start, end) => {
+                    write_class_char(f, *start)?;
+                    f.write_str("-")?;
+                    write_class_char(f, *end)?;
+                }
+                ClassItem::Perl(class) => write!(f, "{}", class)?,
+                ClassItem::Ascii(name) => write!(f, "[:{}:]", name)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses the body of a bracketed class, as passed to `character_class`.
+    ///
+    /// Backslash escapes, `a-z` ranges, Perl classes and `[:name:]` ASCII
+    /// classes are recognized; everything else is taken literally.
+    pub fn parse(negated: bool, body: &str) -> ClassSet {
+        let chars: Vec<char> = body.chars().collect();
+        let mut items = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            if chars[i] == '[' && chars.get(i + 1) == Some(&':') {
+                let rest: String = chars[i + 2..].iter().collect();
+                if let Some(end) = rest.find(":]") {
+                    let name = &rest[..end];
+                    items.push(ClassItem::Ascii(name.to_string()));
+                    i += name.chars().count() + 4;
+                    continue;
+                }
+            }
+            let (item, next) = match chars[i] {
+                '\\' if i + 1 < chars.len() => {
+                    let item = match chars[i + 1] {
+                        'd' => ClassItem::Perl(PerlClass::Digit),
+                        'D' => ClassItem::Perl(PerlClass::NonDigit),
+                        'w' => ClassItem::Perl(PerlClass::Word),
+                        'W' => ClassItem::Perl(PerlClass::NonWord),
+                        's' => ClassItem::Perl(PerlClass::Whitespace),
+                        'S' => ClassItem::Perl(PerlClass::NonWhitespace),
+                        'n' => ClassItem::Char('\n'),
+                        'r' => ClassItem::Char('\r'),
+                        't' => ClassItem::Char('\t'),
+                        c => ClassItem::Char(c),
+                    };
+                    (item, i + 2)
+                }
+                c => (ClassItem::Char(c), i + 1),
+            };
+            i = next;
+            if let ClassItem::Char(start) = item {
+                if chars.get(i) == Some(&'-') && i + 1 < chars.len() {
+                    let (end, next) = if chars[i + 1] == '\\' && i + 2 < chars.len() {
+                        (chars[i + 2], i + 3)
+                    } else {
+                        (chars[i + 1], i + 2)
+                    };
+                    items.push(ClassItem::Range(start, end));
+                    i = next;
+                    continue;
+                }
+            }
+            items.push(item);
+        }
+        ClassSet { negated, items }
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Renderer::new(f, None).node(self)
+    }
+}
+
+/// A node together with the byte range of the pattern text it rendered to.
+#[derive(Debug)]
+pub(crate) struct Span<'a> {
+    pub(crate) range: Range<usize>,
+    pub(crate) node: &'a Node,
+}
+
+/// Renders the top-level nodes of a builder, optionally recording the span
+/// of every node in the tree.
+pub(crate) fn render<'a>(nodes: &'a [Node], spans: Option<&mut Vec<Span<'a>>>) -> String {
+    let mut out = String::new();
+    let mut renderer = Renderer::new(&mut out, spans);
+    let result = match nodes {
+        // A lone alternation needs no group around it.
+        [node] => renderer.bare(node),
+        nodes => nodes.iter().try_for_each(|node| renderer.node(node)),
+    };
+    result.expect("writing to a String cannot fail");
+    out
+}
+
+struct Renderer<'a, 'w> {
+    out: &'w mut dyn fmt::Write,
+    pos: usize,
+    spans: Option<&'w mut Vec<Span<'a>>>,
+}
+
+impl fmt::Write for Renderer<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.pos += s.len();
+        self.out.write_str(s)
+    }
+}
+
+impl<'a, 'w> Renderer<'a, 'w> {
+    fn new(out: &'w mut dyn fmt::Write, spans: Option<&'w mut Vec<Span<'a>>>) -> Self {
+        Renderer { out, pos: 0, spans }
+    }
+
+    fn record(&mut self, start: usize, node: &'a Node) {
+        if let Some(spans) = self.spans.as_mut() {
+            spans.push(Span {
+                range: start..self.pos,
+                node,
+            });
+        }
+    }
+
+    fn node(&mut self, node: &'a Node) -> fmt::Result {
+        let start = self.pos;
+        match node {
+            Node::Empty => {}
+            Node::Raw(text) => self.write_str(text)?,
+            Node::Text(text) => text.chars().try_for_each(|c| write_literal_char(self, c))?,
+            Node::Literal(c) => write_literal_char(self, *c)?,
+            Node::Dot => self.write_str(".")?,
+            Node::Anchor(anchor) => write!(self, "{}", anchor)?,
+            Node::Class(class) => write!(self, "{}", class)?,
+            Node::Repeat { node, repetition, greedy } => {
+                if node.is_atom() {
+                    self.node(node)?;
+                } else {
+                    self.write_str("(?:")?;
+                    self.bare(node)?;
+                    self.write_str(")")?;
+                }
+                write!(self, "{}", repetition)?;
+                if !greedy {
+                    self.write_str("?")?;
+                }
+            }
+            Node::Group { kind, node } => {
+                match kind {
+                    GroupKind::Capturing => self.write_str("(")?,
+                    GroupKind::NonCapturing => self.write_str("(?:")?,
+                    GroupKind::Named(name) => write!(self, "(?P<{}>", name)?,
+                }
+                self.bare(node)?;
+                self.write_str(")")?;
+            }
+            Node::Flags { flags, node } => {
+                write!(self, "(?{}:", flags)?;
+                self.bare(node)?;
+                self.write_str(")")?;
+            }
+            Node::Look { kind, node } => {
+                self.write_str(match kind {
+                    LookKind::PositiveLookahead => "(?=",
+                    LookKind::NegativeLookahead => "(?!",
+                    LookKind::PositiveLookbehind => "(?<=",
+                    LookKind::NegativeLookbehind => "(?<!",
+                })?;
+                self.bare(node)?;
+                self.write_str(")")?;
+            }
+            Node::Backreference(n) => write!(self, "\\{}", n)?,
+            Node::NamedBackreference(name) => write!(self, "\\k<{}>", name)?,
+            Node::Alternation(_) => {
+                self.write_str("(?:")?;
+                self.branches(node)?;
+                self.write_str(")")?;
+            }
+            Node::Concat(nodes) => nodes.iter().try_for_each(|node| self.node(node))?,
+        }
+        self.record(start, node);
+        Ok(())
+    }
+
+    fn bare(&mut self, node: &'a Node) -> fmt::Result {
+        match node {
+            Node::Alternation(_) => {
+                let start = self.pos;
+                self.branches(node)?;
+                self.record(start, node);
+                Ok(())
+            }
+            node => self.node(node),
+        }
+    }
+
+    fn branches(&mut self, node: &'a Node) -> fmt::Result {
+        match node {
+            // An alternation of no branches can never match.
+            Node::Alternation(branches) if branches.is_empty() => self.write_str("[^\\s\\S]"),
+            Node::Alternation(branches) => {
+                for (i, branch) in branches.iter().enumerate() {
+                    if i > 0 {
+                        self.write_str("|")?;
+                    }
+                    self.node(branch)?;
+                }
+                Ok(())
+            }
+            node => self.node(node),
+        }
+    }
+}
+
+impl Flag {
+    /// Returns the letter used for the flag in pattern syntax.
+    pub fn as_char(self) -> char {
+        match self {
+            Flag::CaseInsensitive => 'i',
+            Flag::MultiLine => 'm',
+            Flag::DotMatchesNewLine => 's',
+            Flag::IgnoreWhitespace => 'x',
+            Flag::SwapGreed => 'U',
+            Flag::Crlf => 'R',
+        }
+    }
+}
+
+impl fmt::Display for Flags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for flag in &self.enabled {
+            write!(f, "{}", flag.as_char())?;
+        }
+        if !self.disabled.is_empty() {
+            f.write_str("-")?;
+            for flag in &self.disabled {
+                write!(f, "{}", flag.as_char())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Anchor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Anchor::StartOfLine => "^",
+            Anchor::EndOfLine => "$",
+            Anchor::StartOfText => "\\A",
+            Anchor::EndOfText => "\\z",
+            Anchor::WordBoundary => "\\b",
+            Anchor::NonWordBoundary => "\\B",
+        })
+    }
+}
+
+impl fmt::Display for Repetition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Repetition::ZeroOrMore => f.write_str("*"),
+            Repetition::OneOrMore => f.write_str("+"),
+            Repetition::ZeroOrOne => f.write_str("?"),
+            Repetition::Exactly(n) => write!(f, "{{{}}}", n),
+            Repetition::AtLeast(n) => write!(f, "{{{},}}", n),
+            Repetition::Between(n, m) => write!(f, "{{{},{}}}", n, m),
+        }
+    }
+}
+
+impl fmt::Display for PerlClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PerlClass::Digit => "\\d",
+            PerlClass::NonDigit => "\\D",
+            PerlClass::Word => "\\w",
+            PerlClass::NonWord => "\\W",
+            PerlClass::Whitespace => "\\s",
+            PerlClass::NonWhitespace => "\\S",
+        })
+    }
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Class::Perl(class) => write!(f, "{}", class),
+            Class::Set(set) => write!(f, "{}", set),
+        }
+    }
+}
+
+impl fmt::Display for ClassSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.negated { "[^" } else { "[" })?;
+        self.write_body(f)?;
+        f.write_str("]")
+    }
+}
+
+/// Returns `true` for characters that have a special meaning in patterns.
+pub fn is_meta_character(c: char) -> bool {
+    "\\.+*?()|[]{}^$#&-~".contains(c)
+}
+
+/// Writes a character outside a class, escaped only if it has a special meaning there.
+/// `&`, `-` and `~` only have one inside classes.
+fn write_literal_char<W: Write + ?Sized>(f: &mut W, c: char) -> fmt::Result {
+    match c {
+        '\\' | '.' | '+' | '*' | '?' | '(' | ')' | '|' | '[' | ']' | '{' | '}' | '^' | '$' | '#' => {
+            write!(f, "\\{}", c)
+        }
+        c => write_plain_char(f, c),
+    }
+}
+
+/// Writes a character that needs no escaping for its meaning, spelling out
+/// control characters so that they stay readable, and whitespace so that it
+/// is not ignored under the `x` flag.
+fn write_plain_char<W: Write + ?Sized>(f: &mut W, c: char) -> fmt::Result {
+    match c {
+        '\n' => f.write_str("\\n"),
+        '\r' => f.write_str("\\r"),
+        '\t' => f.write_str("\\t"),
+        ' ' => f.write_str("\\ "),
+        c if c.is_control() || c.is_whitespace() => write!(f, "\\x{{{:X}}}", c as u32),
+        c => write!(f, "{}", c),
+    }
+}
+
+/// Writes a character inside a class. `#` starts a comment there under the `x` flag.
+fn write_class_char<W: Write + ?Sized>(f: &mut W, c: char) -> fmt::Result {
+    match c {
+        '\\' | '[' | ']' | '^' | '-' | '&' | '~' | '#' => write!(f, "\\{}", c),
+        c => write_plain_char(f, c),
+    }
+}
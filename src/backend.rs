@@ -0,0 +1,122 @@
+//! Matching engines that a `RegexBuilder` can be compiled into.
+//!
+//! A [`Backend`] turns the expression tree of a builder into a matcher and
+//! reports up front which constructs of the tree it cannot handle, so that
+//! an unsupported pattern fails before it is compiled. Backends are
+//! implemented for [`regex::Regex`], [`regex::bytes::Regex`] and
+//! [`regex::RegexSet`].
+
+use std::error::Error;
+use std::fmt;
+
+use crate::ast::{LookKind, Node};
+
+/// A node that a [`Backend`] cannot compile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Unsupported<'a> {
+    /// The offending node.
+    pub node: &'a Node,
+    /// The construct that is not supported, such as `"look-around"`.
+    pub construct: &'static str,
+}
+
+impl fmt::Display for Unsupported<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.node.method() {
+            Some(method) => write!(f, "{} in `{}({})`", self.construct, method, self.node.arguments()),
+            None => f.write_str(self.construct),
+        }
+    }
+}
+
+/// A matching engine that a `RegexBuilder` can be compiled into with `RegexBuilder::compile`.
+pub trait Backend: Sized {
+    /// Error returned when the engine rejects a pattern.
+    type Error: Error + Send + Sync + 'static;
+
+    /// Returns the name of the engine, used in error messages.
+    fn name() -> &'static str;
+
+    /// Returns the nodes in the tree under `node` that the engine cannot compile.
+    ///
+    /// The default implementation reports the constructs that the `regex` crate
+    /// does not support: look-around assertions and backreferences.
+    fn unsupported(node: &Node) -> Vec<Unsupported<'_>> {
+        let mut unsupported = Vec::new();
+        node.walk(&mut |node| {
+            let construct = match node {
+                Node::Look { kind: LookKind::PositiveLookahead | LookKind::NegativeLookahead, .. } => "look-ahead",
+                Node::Look { .. } => "look-behind",
+                Node::Backreference(_) | Node::NamedBackreference(_) => "backreferences",
+                _ => return,
+            };
+            unsupported.push(Unsupported { node, construct });
+        });
+        unsupported
+    }
+
+    /// Compiles the sequence of nodes into a matcher.
+    ///
+    /// `pattern` is the sequence rendered as a pattern, as `RegexBuilder::build` returns it.
+    fn compile(nodes: &[Node], pattern: &str) -> Result<Self, Self::Error>;
+}
+
+impl Backend for regex::Regex {
+    type Error = regex::Error;
+
+    fn name() -> &'static str {
+        "regex::Regex"
+    }
+
+    fn compile(_: &[Node], pattern: &str) -> Result<Self, Self::Error> {
+        regex::Regex::new(pattern)
+    }
+}
+
+impl Backend for regex::bytes::Regex {
+    type Error = regex::Error;
+
+    fn name() -> &'static str {
+        "regex::bytes::Regex"
+    }
+
+    fn compile(_: &[Node], pattern: &str) -> Result<Self, Self::Error> {
+        regex::bytes::Regex::new(pattern)
+    }
+}
+
+/// Each branch of a top-level alternation (as built by `any_of`) becomes one
+/// pattern of the set, so `RegexSet::matches` reports the matching branches.
+/// Any other tree becomes a set of a single pattern.
+///
+/// # Example
+///
+/// ```
+/// use regex::RegexSet;
+/// use simple_regex::RegexBuilder;
+///
+/// let set: RegexSet = RegexBuilder::new()
+///     .any_of([
+///         RegexBuilder::new().string("GET"),
+///         RegexBuilder::new().string("POST"),
+///         RegexBuilder::new().one_or_more(RegexBuilder::new().digit()),
+///     ])
+///     .compile()
+///     .unwrap();
+/// let matches: Vec<usize> = set.matches("POST 200").into_iter().collect();
+/// assert_eq!(matches, [1, 2]);
+/// ```
+impl Backend for regex::RegexSet {
+    type Error = regex::Error;
+
+    fn name() -> &'static str {
+        "regex::RegexSet"
+    }
+
+    fn compile(nodes: &[Node], pattern: &str) -> Result<Self, Self::Error> {
+        match nodes {
+            [Node::Alternation(branches)] => regex::RegexSet::new(branches.iter().map(Node::to_string)),
+            _ => regex::RegexSet::new([pattern]),
+        }
+    }
+}
@@ -0,0 +1,895 @@
+//! A bundled backtracking matcher for patterns the `regex` crate rejects.
+//!
+//! The `regex` crate guarantees linear-time matching and therefore does not
+//! support look-around assertions or backreferences. [`Regex`] compiles the
+//! expression tree of a `RegexBuilder` into a small backtracking program
+//! instead, which handles both. Since backtracking can take exponential time,
+//! every search is bounded by a configurable step limit. Like other
+//! backtracking engines, a repetition ends after an iteration that matches the
+//! empty string, so capture positions can differ from the `regex` crate's in
+//! such corner cases. Bounded repetitions are unrolled when compiling, and a
+//! program larger than the `regex` crate's default size limit fails to compile
+//! with [`regex::Error::CompiledTooBig`].
+//!
+//! [`Matcher`] picks the `regex` crate when it can and falls back to this
+//! engine otherwise; `RegexBuilder::to_matcher` builds one.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Index, Range};
+use std::sync::Arc;
+
+use regex_syntax::hir::{self, HirKind};
+
+use crate::ast::{Anchor, Flag, GroupKind, LookKind, Node, Repetition};
+use crate::backend::{Backend, Unsupported};
+
+/// The number of steps a search may take unless configured otherwise.
+pub const DEFAULT_STEP_LIMIT: usize = 1_000_000;
+
+/// The approximate size in bytes that a compiled program may take, the `regex`
+/// crate's default size limit.
+const SIZE_LIMIT: usize = 10 * (1 << 20);
+
+/// Error returned when a search exceeds the step limit of a [`Regex`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StepLimitExceeded {
+    /// The limit that was exceeded.
+    pub limit: usize,
+}
+
+impl fmt::Display for StepLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "backtracking step limit of {} exceeded", self.limit)
+    }
+}
+
+impl std::error::Error for StepLimitExceeded {}
+
+/// A single match of a pattern in a haystack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Match<'h> {
+    haystack: &'h str,
+    start: usize,
+    end: usize,
+}
+
+impl<'h> Match<'h> {
+    /// Returns the byte offset where the match starts.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the byte offset where the match ends.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Returns the byte range of the match.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns the matched text.
+    pub fn as_str(&self) -> &'h str {
+        &self.haystack[self.start..self.end]
+    }
+}
+
+/// The capturing groups of a single match, group 0 being the whole match.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Captures<'h> {
+    haystack: &'h str,
+    groups: Vec<Option<(usize, usize)>>,
+    names: Arc<[Option<String>]>,
+}
+
+impl<'h> Captures<'h> {
+    /// Returns the match of the group with the given index, if it participated in the match.
+    pub fn get(&self, index: usize) -> Option<Match<'h>> {
+        self.groups.get(index).copied().flatten().map(|(start, end)| Match {
+            haystack: self.haystack,
+            start,
+            end,
+        })
+    }
+
+    /// Returns the match of the group with the given name, if it participated in the match.
+    pub fn name(&self, name: &str) -> Option<Match<'h>> {
+        let index = self.names.iter().position(|group| group.as_deref() == Some(name))?;
+        self.get(index)
+    }
+
+    /// Returns the number of groups, including group 0.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Returns `true` if there are no groups, which never happens for a successful match.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
+
+impl Index<usize> for Captures<'_> {
+    type Output = str;
+
+    /// Returns the text of the group with the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the group did not participate in the match.
+    fn index(&self, index: usize) -> &str {
+        self.get(index)
+            .map(|m| m.as_str())
+            .unwrap_or_else(|| panic!("no group at index '{}'", index))
+    }
+}
+
+/// A backtracking matcher compiled from an expression tree.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::RegexBuilder;
+/// use simple_regex::backtrack;
+///
+/// // A word, a space, and the same word again.
+/// let regex: backtrack::Regex = RegexBuilder::new()
+///     .capturing_group(RegexBuilder::new().one_or_more(RegexBuilder::new().word_character()))
+///     .literal(' ')
+///     .backreference(1)
+///     .compile()
+///     .unwrap();
+/// assert!(regex.is_match("it is is fine"));
+/// assert_eq!(&regex.captures("it is is fine").unwrap()[1], "is");
+/// assert!(!regex.is_match("it is fine"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Regex {
+    pattern: String,
+    programs: Vec<Vec<Inst>>,
+    groups: usize,
+    /// The name of each group, group 0 included.
+    names: Arc<[Option<String>]>,
+    registers: usize,
+    word: Box<[(char, char)]>,
+    step_limit: usize,
+}
+
+#[derive(Clone, Debug)]
+enum Inst {
+    /// Consumes one character within the given ranges.
+    Class(Box<[(char, char)]>),
+    Assert(Assertion),
+    /// Continues at the first target, backtracking to the second.
+    Split(usize, usize),
+    Jmp(usize),
+    /// Stores the current position in a capture slot.
+    Save(usize),
+    /// Stores the current position in a register.
+    Mark(usize),
+    /// Leaves a loop at the given target if the position has not moved since
+    /// the register was marked, so that an empty iteration ends the loop.
+    Progress(usize, usize),
+    Backref { group: usize, case_insensitive: bool },
+    Look { program: usize, kind: LookKind },
+    Match,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Assertion {
+    StartText,
+    EndText,
+    StartLine { crlf: bool },
+    EndLine { crlf: bool },
+    WordBoundary,
+    NonWordBoundary,
+}
+
+enum Frame {
+    Try(usize, usize),
+    Slot(usize, Option<usize>),
+    Register(usize, usize),
+}
+
+impl Regex {
+    /// Returns a copy of the matcher that gives up after `limit` steps per search.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    /// use simple_regex::backtrack;
+    ///
+    /// let regex: backtrack::Regex = RegexBuilder::new()
+    ///     .one_or_more(RegexBuilder::new().zero_or_more(RegexBuilder::new().literal('a')))
+    ///     .literal('b')
+    ///     .compile()
+    ///     .unwrap();
+    /// let regex = regex.with_step_limit(1_000);
+    /// assert!(regex.try_is_match(&"a".repeat(30)).is_err());
+    /// ```
+    pub fn with_step_limit(mut self, limit: usize) -> Self {
+        self.step_limit = limit;
+        self
+    }
+
+    /// Returns the number of steps a search may take.
+    pub fn step_limit(&self) -> usize {
+        self.step_limit
+    }
+
+    /// Returns the pattern the matcher was compiled from.
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns the number of capturing groups, including group 0.
+    pub fn captures_len(&self) -> usize {
+        self.groups
+    }
+
+    /// Returns `true` if the pattern matches anywhere in the haystack.
+    ///
+    /// A search that exceeds the step limit is reported as no match; use
+    /// [`Regex::try_is_match`] to tell the two apart.
+    pub fn is_match(&self, haystack: &str) -> bool {
+        self.try_is_match(haystack).unwrap_or(false)
+    }
+
+    /// Returns the leftmost-first match in the haystack.
+    ///
+    /// A search that exceeds the step limit is reported as no match.
+    pub fn find<'h>(&self, haystack: &'h str) -> Option<Match<'h>> {
+        self.try_find(haystack).unwrap_or(None)
+    }
+
+    /// Returns the capturing groups of the leftmost-first match in the haystack.
+    ///
+    /// A search that exceeds the step limit is reported as no match.
+    pub fn captures<'h>(&self, haystack: &'h str) -> Option<Captures<'h>> {
+        self.try_captures(haystack).unwrap_or(None)
+    }
+
+    /// Like [`Regex::is_match`], but reports a search that exceeds the step limit.
+    pub fn try_is_match(&self, haystack: &str) -> Result<bool, StepLimitExceeded> {
+        Ok(self.try_captures(haystack)?.is_some())
+    }
+
+    /// Like [`Regex::find`], but reports a search that exceeds the step limit.
+    pub fn try_find<'h>(&self, haystack: &'h str) -> Result<Option<Match<'h>>, StepLimitExceeded> {
+        Ok(self.try_captures(haystack)?.and_then(|captures| captures.get(0)))
+    }
+
+    /// Like [`Regex::captures`], but reports a search that exceeds the step limit.
+    pub fn try_captures<'h>(&self, haystack: &'h str) -> Result<Option<Captures<'h>>, StepLimitExceeded> {
+        let mut search = Search {
+            regex: self,
+            haystack,
+            steps: 0,
+        };
+        let starts = haystack.char_indices().map(|(i, _)| i).chain([haystack.len()]);
+        for start in starts {
+            let mut slots = vec![None; self.groups * 2];
+            let mut registers = vec![usize::MAX; self.registers];
+            if let Some(end) = search.run(0, start, None, &mut slots, &mut registers)? {
+                slots[0] = Some(start);
+                slots[1] = Some(end);
+                let groups = slots.chunks(2).map(|slot| slot[0].zip(slot[1])).collect();
+                return Ok(Some(Captures {
+                    haystack,
+                    groups,
+                    names: self.names.clone(),
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+struct Search<'r, 'h> {
+    regex: &'r Regex,
+    haystack: &'h str,
+    steps: usize,
+}
+
+impl Search<'_, '_> {
+    fn next_char(&self, pos: usize) -> Option<char> {
+        self.haystack[pos..].chars().next()
+    }
+
+    fn prev_char(&self, pos: usize) -> Option<char> {
+        self.haystack[..pos].chars().next_back()
+    }
+
+    fn is_word(&self, c: Option<char>) -> bool {
+        c.is_some_and(|c| contains(&self.regex.word, c))
+    }
+
+    fn holds(&self, assertion: Assertion, pos: usize) -> bool {
+        let (prev, next) = (self.prev_char(pos), self.next_char(pos));
+        match assertion {
+            Assertion::StartText => pos == 0,
+            Assertion::EndText => pos == self.haystack.len(),
+            Assertion::StartLine { crlf: false } => prev.is_none_or(|c| c == '\n'),
+            Assertion::EndLine { crlf: false } => next.is_none_or(|c| c == '\n'),
+            Assertion::StartLine { crlf: true } => match prev {
+                None | Some('\n') => true,
+                Some('\r') => next != Some('\n'),
+                _ => false,
+            },
+            Assertion::EndLine { crlf: true } => match next {
+                None | Some('\r') => true,
+                Some('\n') => prev != Some('\r'),
+                _ => false,
+            },
+            Assertion::WordBoundary => self.is_word(prev) != self.is_word(next),
+            Assertion::NonWordBoundary => self.is_word(prev) == self.is_word(next),
+        }
+    }
+
+    /// Returns the length of the haystack text at `pos` equal to `text`.
+    fn backref(&self, text: &str, pos: usize, case_insensitive: bool) -> Option<usize> {
+        if !case_insensitive {
+            return self.haystack[pos..].starts_with(text).then_some(text.len());
+        }
+        let mut rest = self.haystack[pos..].char_indices();
+        for expected in text.chars() {
+            let (_, actual) = rest.next()?;
+            if expected != actual && !expected.to_lowercase().eq(actual.to_lowercase()) {
+                return None;
+            }
+        }
+        Some(rest.next().map_or(self.haystack.len() - pos, |(i, _)| i))
+    }
+
+    /// Runs a program from `start`, returning the end of the first match.
+    /// With `end` set, only a match ending there is accepted.
+    fn run(
+        &mut self,
+        program: usize,
+        start: usize,
+        end: Option<usize>,
+        slots: &mut [Option<usize>],
+        registers: &mut [usize],
+    ) -> Result<Option<usize>, StepLimitExceeded> {
+        let insts = &self.regex.programs[program];
+        let mut stack = vec![Frame::Try(0, start)];
+        while let Some(frame) = stack.pop() {
+            let (mut pc, mut pos) = match frame {
+                Frame::Try(pc, pos) => (pc, pos),
+                Frame::Slot(slot, old) => {
+                    slots[slot] = old;
+                    continue;
+                }
+                Frame::Register(register, old) => {
+                    registers[register] = old;
+                    continue;
+                }
+            };
+            loop {
+                self.steps += 1;
+                if self.steps > self.regex.step_limit {
+                    return Err(StepLimitExceeded {
+                        limit: self.regex.step_limit,
+                    });
+                }
+                match &insts[pc] {
+                    Inst::Class(ranges) => match self.next_char(pos) {
+                        Some(c) if contains(ranges, c) => {
+                            pos += c.len_utf8();
+                            pc += 1;
+                        }
+                        _ => break,
+                    },
+                    Inst::Assert(assertion) => {
+                        if !self.holds(*assertion, pos) {
+                            break;
+                        }
+                        pc += 1;
+                    }
+                    Inst::Split(first, second) => {
+                        stack.push(Frame::Try(*second, pos));
+                        pc = *first;
+                    }
+                    Inst::Jmp(target) => pc = *target,
+                    Inst::Save(slot) => {
+                        stack.push(Frame::Slot(*slot, slots[*slot]));
+                        slots[*slot] = Some(pos);
+                        pc += 1;
+                    }
+                    Inst::Mark(register) => {
+                        stack.push(Frame::Register(*register, registers[*register]));
+                        registers[*register] = pos;
+                        pc += 1;
+                    }
+                    Inst::Progress(register, exit) => {
+                        pc = if registers[*register] == pos { *exit } else { pc + 1 };
+                    }
+                    Inst::Backref { group, case_insensitive } => {
+                        let Some((from, to)) = slots[group * 2].zip(slots[group * 2 + 1]) else {
+                            break;
+                        };
+                        let text = &self.haystack[from..to];
+                        match self.backref(text, pos, *case_insensitive) {
+                            Some(len) => {
+                                pos += len;
+                                pc += 1;
+                            }
+                            None => break,
+                        }
+                    }
+                    Inst::Look { program, kind } => {
+                        let mut inner = slots.to_vec();
+                        let matched = match kind {
+                            LookKind::PositiveLookahead | LookKind::NegativeLookahead => {
+                                self.run(*program, pos, None, &mut inner, &mut registers.to_vec())?.is_some()
+                            }
+                            LookKind::PositiveLookbehind | LookKind::NegativeLookbehind => {
+                                let mut matched = false;
+                                let starts = self.haystack[..pos].char_indices().map(|(i, _)| i).rev();
+                                for from in [pos].into_iter().chain(starts) {
+                                    inner.copy_from_slice(slots);
+                                    let found = self.run(*program, from, Some(pos), &mut inner, &mut registers.to_vec())?;
+                                    if found.is_some() {
+                                        matched = true;
+                                        break;
+                                    }
+                                }
+                                matched
+                            }
+                        };
+                        let positive = matches!(kind, LookKind::PositiveLookahead | LookKind::PositiveLookbehind);
+                        if matched != positive {
+                            break;
+                        }
+                        if positive {
+                            // Groups captured inside a positive look-around stay visible.
+                            for (slot, value) in inner.into_iter().enumerate() {
+                                if slots[slot] != value {
+                                    stack.push(Frame::Slot(slot, slots[slot]));
+                                    slots[slot] = value;
+                                }
+                            }
+                        }
+                        pc += 1;
+                    }
+                    Inst::Match => {
+                        if end.is_none_or(|end| end == pos) {
+                            return Ok(Some(pos));
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn contains(ranges: &[(char, char)], c: char) -> bool {
+    ranges
+        .binary_search_by(|&(start, end)| {
+            if end < c {
+                std::cmp::Ordering::Less
+            } else if start > c {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+/// The flags in effect while compiling a node.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Context {
+    pub(crate) case_insensitive: bool,
+    pub(crate) multi_line: bool,
+    dot_matches_new_line: bool,
+    swap_greed: bool,
+    crlf: bool,
+}
+
+impl Context {
+    pub(crate) fn set(&mut self, flag: Flag, value: bool) {
+        match flag {
+            Flag::CaseInsensitive => self.case_insensitive = value,
+            Flag::MultiLine => self.multi_line = value,
+            Flag::DotMatchesNewLine => self.dot_matches_new_line = value,
+            Flag::SwapGreed => self.swap_greed = value,
+            Flag::Crlf => self.crlf = value,
+            Flag::IgnoreWhitespace => {}
+        }
+    }
+
+    /// Wraps class syntax in the flags that change which characters it matches.
+    pub(crate) fn scope(&self, syntax: &str) -> String {
+        let flags = self.class_flags();
+        if flags.is_empty() {
+            syntax.to_string()
+        } else {
+            format!("(?{}:{})", flags, syntax)
+        }
+    }
+
+    /// Flags that change which characters a class matches.
+    fn class_flags(&self) -> String {
+        let mut flags = String::new();
+        for (set, flag) in [
+            (self.case_insensitive, 'i'),
+            (self.dot_matches_new_line, 's'),
+            (self.crlf, 'R'),
+        ] {
+            if set {
+                flags.push(flag);
+            }
+        }
+        flags
+    }
+}
+
+/// Returns the ranges of characters matched by the given class syntax, resolved
+/// by `regex-syntax` so that they agree with the `regex` crate. Syntax that is not
+/// a single class or character matches nothing.
+pub(crate) fn class_ranges(pattern: &str) -> Result<Box<[(char, char)]>, Box<regex_syntax::Error>> {
+    let hir = regex_syntax::Parser::new().parse(pattern)?;
+    Ok(match hir.kind() {
+        HirKind::Class(hir::Class::Unicode(class)) => {
+            class.ranges().iter().map(|range| (range.start(), range.end())).collect()
+        }
+        HirKind::Literal(hir::Literal(bytes)) => {
+            let c = std::str::from_utf8(bytes).ok().and_then(|s| s.chars().next()).unwrap_or('\0');
+            vec![(c, c)].into()
+        }
+        _ => Box::new([]),
+    })
+}
+
+/// Returns the error the `regex` crate reports for a repetition whose maximum is below its minimum.
+pub(crate) fn reversed_range(repetition: Repetition) -> Box<regex_syntax::Error> {
+    let pattern = format!("a{}", repetition);
+    Box::new(regex_syntax::Parser::new().parse(&pattern).expect_err("the range is reversed"))
+}
+
+/// Converts an error of `regex-syntax` into the error the `regex` crate reports for it.
+fn syntax_error(error: Box<regex_syntax::Error>) -> regex::Error {
+    regex::Error::Syntax(error.to_string())
+}
+
+struct Compiler<'a> {
+    programs: Vec<Vec<Inst>>,
+    /// The number of instructions in `programs`.
+    size: usize,
+    groups: Vec<&'a Node>,
+    registers: usize,
+    classes: HashMap<String, Box<[(char, char)]>>,
+}
+
+impl<'a> Compiler<'a> {
+    /// Returns the ranges of characters matched by the given class syntax,
+    /// caching them by pattern.
+    fn ranges(&mut self, syntax: &str, context: Context) -> Result<Box<[(char, char)]>, regex::Error> {
+        let pattern = context.scope(syntax);
+        if let Some(ranges) = self.classes.get(&pattern) {
+            return Ok(ranges.clone());
+        }
+        let ranges = class_ranges(&pattern).map_err(syntax_error)?;
+        self.classes.insert(pattern, ranges.clone());
+        Ok(ranges)
+    }
+
+    fn group_index(&self, node: &Node) -> usize {
+        self.groups.iter().position(|group| std::ptr::eq(*group, node)).unwrap() + 1
+    }
+
+    fn named_group_index(&self, name: &str) -> Option<usize> {
+        let position = self.groups.iter().position(|group| {
+            matches!(group, Node::Group { kind: GroupKind::Named(group), .. } if group == name)
+        })?;
+        Some(position + 1)
+    }
+
+    /// Fails once the finished programs and `out` together exceed the size limit.
+    fn check_size(&self, out: &[Inst]) -> Result<(), regex::Error> {
+        if (self.size + out.len()) * std::mem::size_of::<Inst>() > SIZE_LIMIT {
+            return Err(regex::Error::CompiledTooBig(SIZE_LIMIT));
+        }
+        Ok(())
+    }
+
+    fn compile(&mut self, node: &'a Node, context: Context, out: &mut Vec<Inst>) -> Result<(), regex::Error> {
+        match node {
+            Node::Empty | Node::Raw(_) => {}
+            Node::Literal(c) => {
+                let ranges = self.ranges(&Node::Literal(*c).to_string(), context)?;
+                out.push(Inst::Class(ranges));
+            }
+            Node::Text(text) => {
+                for c in text.chars() {
+                    let ranges = self.ranges(&Node::Literal(c).to_string(), context)?;
+                    out.push(Inst::Class(ranges));
+                }
+            }
+            Node::Dot | Node::Class(_) => {
+                let ranges = self.ranges(&node.to_string(), context)?;
+                out.push(Inst::Class(ranges));
+            }
+            Node::Anchor(anchor) => {
+                let assertion = match anchor {
+                    Anchor::StartOfLine if context.multi_line => Assertion::StartLine { crlf: context.crlf },
+                    Anchor::EndOfLine if context.multi_line => Assertion::EndLine { crlf: context.crlf },
+                    Anchor::StartOfLine | Anchor::StartOfText => Assertion::StartText,
+                    Anchor::EndOfLine | Anchor::EndOfText => Assertion::EndText,
+                    Anchor::WordBoundary => Assertion::WordBoundary,
+                    Anchor::NonWordBoundary => Assertion::NonWordBoundary,
+                };
+                out.push(Inst::Assert(assertion));
+            }
+            Node::Repeat { node, repetition, greedy } => {
+                let (min, max) = match *repetition {
+                    Repetition::ZeroOrMore => (0, None),
+                    Repetition::OneOrMore => (1, None),
+                    Repetition::ZeroOrOne => (0, Some(1)),
+                    Repetition::Exactly(n) => (n, Some(n)),
+                    Repetition::AtLeast(n) => (n, None),
+                    Repetition::Between(n, m) if m < n => return Err(syntax_error(reversed_range(*repetition))),
+                    Repetition::Between(n, m) => (n, Some(m)),
+                };
+                self.repeat(node, min, max, *greedy != context.swap_greed, context, out)?;
+            }
+            Node::Group { kind, node: inner } => match kind {
+                GroupKind::Capturing | GroupKind::Named(_) => {
+                    let group = self.group_index(node);
+                    out.push(Inst::Save(group * 2));
+                    self.compile(inner, context, out)?;
+                    out.push(Inst::Save(group * 2 + 1));
+                }
+                GroupKind::NonCapturing => self.compile(inner, context, out)?,
+            },
+            Node::Flags { flags, node } => {
+                let mut context = context;
+                flags.enabled.iter().for_each(|flag| context.set(*flag, true));
+                flags.disabled.iter().for_each(|flag| context.set(*flag, false));
+                self.compile(node, context, out)?;
+            }
+            Node::Look { kind, node } => {
+                let mut program = Vec::new();
+                self.compile(node, context, &mut program)?;
+                program.push(Inst::Match);
+                self.size += program.len();
+                self.programs.push(program);
+                out.push(Inst::Look {
+                    program: self.programs.len() - 1,
+                    kind: *kind,
+                });
+            }
+            Node::Backreference(group) if (1..=self.groups.len()).contains(group) => out.push(Inst::Backref {
+                group: *group,
+                case_insensitive: context.case_insensitive,
+            }),
+            // A backreference to a group that does not exist never matches.
+            Node::Backreference(_) => out.push(Inst::Class(Box::new([]))),
+            Node::NamedBackreference(name) => match self.named_group_index(name) {
+                Some(group) => out.push(Inst::Backref {
+                    group,
+                    case_insensitive: context.case_insensitive,
+                }),
+                // A backreference to a group that does not exist never matches.
+                None => out.push(Inst::Class(Box::new([]))),
+            },
+            Node::Alternation(branches) => {
+                if branches.is_empty() {
+                    out.push(Inst::Class(Box::new([])));
+                    return Ok(());
+                }
+                let mut jumps = Vec::new();
+                for (i, branch) in branches.iter().enumerate() {
+                    if i + 1 == branches.len() {
+                        self.compile(branch, context, out)?;
+                        break;
+                    }
+                    let split = out.len();
+                    out.push(Inst::Split(split + 1, 0));
+                    self.compile(branch, context, out)?;
+                    jumps.push(out.len());
+                    out.push(Inst::Jmp(0));
+                    out[split] = Inst::Split(split + 1, out.len());
+                }
+                let end = out.len();
+                for jump in jumps {
+                    out[jump] = Inst::Jmp(end);
+                }
+            }
+            Node::Concat(nodes) => {
+                for node in nodes {
+                    self.compile(node, context, out)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn repeat(
+        &mut self,
+        node: &'a Node,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+        context: Context,
+        out: &mut Vec<Inst>,
+    ) -> Result<(), regex::Error> {
+        for _ in 0..min {
+            let size = self.size + out.len();
+            self.compile(node, context, out)?;
+            if self.size + out.len() == size {
+                // The node compiles to nothing, however often it is repeated.
+                break;
+            }
+            self.check_size(out)?;
+        }
+        let split = |out: &mut Vec<Inst>, at: usize, body: usize, exit: usize| {
+            out[at] = if greedy { Inst::Split(body, exit) } else { Inst::Split(exit, body) };
+        };
+        match max {
+            None => {
+                // A loop that stops after an iteration matching the empty string.
+                let register = self.registers;
+                self.registers += 1;
+                let start = out.len();
+                out.push(Inst::Jmp(0));
+                out.push(Inst::Mark(register));
+                self.compile(node, context, out)?;
+                let progress = out.len();
+                out.push(Inst::Progress(register, 0));
+                out.push(Inst::Jmp(start));
+                let exit = out.len();
+                out[progress] = Inst::Progress(register, exit);
+                split(out, start, start + 1, exit);
+            }
+            Some(max) => {
+                let mut splits = Vec::new();
+                for _ in min..max {
+                    splits.push(out.len());
+                    out.push(Inst::Jmp(0));
+                    self.compile(node, context, out)?;
+                    self.check_size(out)?;
+                }
+                let exit = out.len();
+                for at in splits {
+                    split(out, at, at + 1, exit);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Backend for Regex {
+    type Error = regex::Error;
+
+    fn name() -> &'static str {
+        "simple_regex::backtrack::Regex"
+    }
+
+    fn unsupported(node: &Node) -> Vec<Unsupported<'_>> {
+        let mut unsupported = Vec::new();
+        node.walk(&mut |node| {
+            if let Node::Raw(_) = node {
+                unsupported.push(Unsupported {
+                    node,
+                    construct: "raw pattern text",
+                });
+            }
+        });
+        unsupported
+    }
+
+    fn compile(nodes: &[Node], pattern: &str) -> Result<Self, Self::Error> {
+        let mut groups = Vec::new();
+        for node in nodes {
+            node.walk(&mut |node| {
+                if let Node::Group { kind: GroupKind::Capturing | GroupKind::Named(_), .. } = node {
+                    groups.push(node);
+                }
+            });
+        }
+        let mut compiler = Compiler {
+            programs: vec![Vec::new()],
+            size: 0,
+            groups,
+            registers: 0,
+            classes: HashMap::new(),
+        };
+        let mut main = Vec::new();
+        for node in nodes {
+            compiler.compile(node, Context::default(), &mut main)?;
+        }
+        main.push(Inst::Match);
+        compiler.programs[0] = main;
+        let word = compiler.ranges("\\w", Context::default())?;
+        let names = std::iter::once(None)
+            .chain(compiler.groups.iter().map(|group| match group {
+                Node::Group { kind: GroupKind::Named(name), .. } => Some(name.clone()),
+                _ => None,
+            }))
+            .collect();
+        Ok(Regex {
+            pattern: pattern.to_string(),
+            programs: compiler.programs,
+            groups: compiler.groups.len() + 1,
+            names,
+            registers: compiler.registers,
+            word,
+            step_limit: DEFAULT_STEP_LIMIT,
+        })
+    }
+}
+
+/// A compiled pattern that uses the `regex` crate when it supports every construct in
+/// the builder, and the bundled backtracking [`Regex`] otherwise.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::RegexBuilder;
+/// use simple_regex::backtrack::Matcher;
+///
+/// let matcher = RegexBuilder::new()
+///     .one_or_more(RegexBuilder::new().digit())
+///     .positive_lookahead(RegexBuilder::new().string("px"))
+///     .to_matcher()
+///     .unwrap();
+/// assert!(matches!(matcher, Matcher::Backtracking(_)));
+/// assert_eq!(matcher.find("width: 120px").unwrap().as_str(), "120");
+/// ```
+#[derive(Clone, Debug)]
+pub enum Matcher {
+    /// The pattern is compiled by the `regex` crate.
+    Regex(regex::Regex),
+    /// The pattern is compiled by the bundled backtracking engine.
+    Backtracking(Regex),
+}
+
+impl Matcher {
+    /// Sets the step limit of a backtracking matcher; the `regex` crate needs none.
+    pub fn with_step_limit(self, limit: usize) -> Self {
+        match self {
+            Matcher::Backtracking(regex) => Matcher::Backtracking(regex.with_step_limit(limit)),
+            matcher => matcher,
+        }
+    }
+
+    /// Returns `true` if the pattern matches anywhere in the haystack.
+    pub fn is_match(&self, haystack: &str) -> bool {
+        match self {
+            Matcher::Regex(regex) => regex.is_match(haystack),
+            Matcher::Backtracking(regex) => regex.is_match(haystack),
+        }
+    }
+
+    /// Returns the leftmost-first match in the haystack.
+    pub fn find<'h>(&self, haystack: &'h str) -> Option<Match<'h>> {
+        match self {
+            Matcher::Regex(regex) => regex.find(haystack).map(|m| Match {
+                haystack,
+                start: m.start(),
+                end: m.end(),
+            }),
+            Matcher::Backtracking(regex) => regex.find(haystack),
+        }
+    }
+
+    /// Returns the capturing groups of the leftmost-first match in the haystack.
+    pub fn captures<'h>(&self, haystack: &'h str) -> Option<Captures<'h>> {
+        match self {
+            Matcher::Regex(regex) => regex.captures(haystack).map(|captures| Captures {
+                haystack,
+                groups: captures.iter().map(|group| group.map(|m| (m.start(), m.end()))).collect(),
+                names: regex.capture_names().map(|name| name.map(str::to_string)).collect(),
+            }),
+            Matcher::Backtracking(regex) => regex.captures(haystack),
+        }
+    }
+}
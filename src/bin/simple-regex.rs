@@ -0,0 +1,56 @@
+//! Command line tool for working with patterns through `RegexBuilder`.
+
+use std::io::Read;
+use std::process::ExitCode;
+
+use simple_regex::RegexBuilder;
+
+const USAGE: &str = "\
+Usage: simple-regex <COMMAND> <PATTERN>
+
+Commands:
+  to-rust    Print the RegexBuilder calls that build PATTERN
+  explain    Describe PATTERN in plain English
+
+Pass `-` as PATTERN to read it from standard input.";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (command, pattern) = match args.as_slice() {
+        [flag] if flag == "-h" || flag == "--help" => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        [command, pattern] => (command.as_str(), pattern.clone()),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let pattern = if pattern == "-" {
+        let mut input = String::new();
+        if let Err(error) = std::io::stdin().read_to_string(&mut input) {
+            eprintln!("error: failed to read the pattern: {}", error);
+            return ExitCode::FAILURE;
+        }
+        input.trim_end_matches(['\r', '\n']).to_string()
+    } else {
+        pattern
+    };
+    let builder = match RegexBuilder::parse(&pattern) {
+        Ok(builder) => builder,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    match command {
+        "to-rust" => println!("{}", builder.to_rust_code()),
+        "explain" => println!("{}", builder.explain()),
+        command => {
+            eprintln!("error: unknown command `{}`\n\n{}", command, USAGE);
+            return ExitCode::from(2);
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,301 @@
+//! Generation of Rust code that rebuilds an expression tree with `RegexBuilder` calls.
+
+use crate::ast::{Anchor, Class, ClassItem, ClassSet, GroupKind, Node, PerlClass, Repetition};
+
+/// Width above which a chain of calls is split over several lines, as rustfmt does.
+const CHAIN_WIDTH: usize = 60;
+/// Width above which a call is split over several lines, as rustfmt does.
+const MAX_WIDTH: usize = 100;
+/// Rust keywords, which cannot name the variable holding a group handle.
+const KEYWORDS: &[&str] = &[
+    "_", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro",
+    "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "self", "static", "struct", "super",
+    "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// A single builder method call.
+struct Call {
+    method: &'static str,
+    args: Vec<Arg>,
+}
+
+enum Arg {
+    /// A Rust expression such as a literal.
+    Code(String),
+    /// A nested `RegexBuilder`.
+    Builder(Builder),
+    /// An array of nested builders, as passed to `any_of`.
+    Builders(Vec<Builder>),
+}
+
+/// A chain of builder calls.
+#[derive(Default)]
+struct Builder {
+    /// The variable that `let` statements bind the builder to, once it needs one.
+    variable: Option<String>,
+    /// Whether the chain starts from `variable` rather than `RegexBuilder::new()`.
+    bound: bool,
+    calls: Vec<Call>,
+}
+
+/// Code generation state. `named_group` returns a group handle along with the
+/// builder, so each named group ends a chain with a `let` statement binding both.
+#[derive(Default)]
+struct Writer {
+    statements: String,
+    /// Group names with the variables holding their handles.
+    handles: Vec<(String, String)>,
+    /// The number of nested builders bound to variables.
+    builders: usize,
+}
+
+/// Returns Rust code for a `RegexBuilder` expression that appends `nodes`, preceded
+/// by the `let` statements binding its named groups.
+pub(crate) fn rust_code(nodes: &[Node]) -> String {
+    let mut writer = Writer::default();
+    let mut builder = Builder {
+        variable: Some("builder".to_string()),
+        ..Builder::default()
+    };
+    nodes.iter().for_each(|node| writer.push_calls(node, &mut builder));
+    let mut out = writer.statements;
+    write_builder(&builder, 0, &mut out);
+    out
+}
+
+fn call(method: &'static str, args: Vec<Arg>) -> Call {
+    Call { method, args }
+}
+
+impl Writer {
+    fn builder(&mut self, node: &Node) -> Builder {
+        let mut builder = Builder::default();
+        self.push_calls(node, &mut builder);
+        builder
+    }
+
+    /// Writes a `let` statement binding the calls of `builder` so far, which end in a
+    /// `named_group` call, to the builder's variable and `handle`.
+    fn bind(&mut self, builder: &mut Builder, handle: &str) {
+        let variable = builder.variable.get_or_insert_with(|| {
+            self.builders += 1;
+            format!("builder{}", self.builders)
+        });
+        let mut out = format!("let ({}, {}) = ", variable, handle);
+        write_builder(builder, 0, &mut out);
+        self.statements.push_str(&out);
+        self.statements.push_str(";\n");
+        builder.bound = true;
+        builder.calls.clear();
+    }
+
+    /// Returns the variable for the handle of the group `name`: the name itself when it
+    /// is an identifier free for use, or else one derived from it.
+    fn handle(&mut self, name: &str) -> String {
+        let identifier = !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        let mut variable = if identifier && !KEYWORDS.contains(&name) && !name.starts_with("builder") {
+            name.to_string()
+        } else {
+            format!("group_{}", name.replace(|c: char| !c.is_ascii_alphanumeric(), "_"))
+        };
+        while self.handles.iter().any(|(_, handle)| *handle == variable) {
+            variable.push('_');
+        }
+        self.handles.push((name.to_string(), variable.clone()));
+        variable
+    }
+
+    fn push_calls(&mut self, node: &Node, builder: &mut Builder) {
+        let code = |code: String| vec![Arg::Code(code)];
+        let call = match node {
+            Node::Empty => return,
+            Node::Concat(nodes) => return nodes.iter().for_each(|node| self.push_calls(node, builder)),
+            Node::Raw(text) => call("raw", code(string_literal(text))),
+            Node::Literal(c) => call("literal", code(format!("{:?}", c))),
+            Node::Text(text) => call("string", code(string_literal(text))),
+            Node::Dot => call("dot", Vec::new()),
+            Node::Anchor(Anchor::StartOfText) => call("escape", code("'A'".to_string())),
+            Node::Anchor(Anchor::EndOfText) => call("escape", code("'z'".to_string())),
+            Node::Anchor(_) | Node::Class(Class::Perl(_)) => call(node.method().unwrap(), Vec::new()),
+            Node::Class(Class::Set(set)) => match (set.negated, set.items.as_slice()) {
+                (false, [ClassItem::Range(start, end)]) => {
+                    let args = vec![Arg::Code(format!("{:?}", start)), Arg::Code(format!("{:?}", end))];
+                    call("range_character_class", args)
+                }
+                (false, [ClassItem::Char('-'), ClassItem::Perl(PerlClass::Whitespace)]) => {
+                    call("dash_space_character_class", Vec::new())
+                }
+                // Bodies that `character_class` would read back differently stay raw.
+                _ if ClassSet::parse(set.negated, &set.body()) != *set => {
+                    call("raw", code(string_literal(&node.to_string())))
+                }
+                _ => call(node.method().unwrap(), code(string_literal(&set.body()))),
+            },
+            Node::Repeat { node: inner, repetition, greedy: false } => call(
+                "lazy",
+                vec![Arg::Builder(self.builder(&Node::Repeat {
+                    node: inner.clone(),
+                    repetition: *repetition,
+                    greedy: true,
+                }))],
+            ),
+            Node::Repeat { node: inner, repetition, .. } => {
+                let inner = match (repetition, inner.as_ref()) {
+                    (Repetition::ZeroOrOne, Node::Group { kind: GroupKind::Capturing, node }) => node,
+                    _ => inner,
+                };
+                let mut args = vec![Arg::Builder(self.builder(inner))];
+                match repetition {
+                    Repetition::Exactly(n) | Repetition::AtLeast(n) => args.push(Arg::Code(n.to_string())),
+                    Repetition::Between(n, m) => {
+                        args.push(Arg::Code(n.to_string()));
+                        args.push(Arg::Code(m.to_string()));
+                    }
+                    _ => {}
+                }
+                call(node.method().unwrap(), args)
+            }
+            Node::Group { kind: GroupKind::Named(name), node: inner } => {
+                let inner = self.builder(inner);
+                builder.calls.push(call("named_group", vec![Arg::Code(string_literal(name)), Arg::Builder(inner)]));
+                let handle = self.handle(name);
+                return self.bind(builder, &handle);
+            }
+            Node::NamedBackreference(name) => match self.handles.iter().find(|(group, _)| group == name) {
+                Some((_, handle)) => call("backreference_to", code(format!("&{}", handle))),
+                // A group that has not ended yet has no handle.
+                None => call("raw", code(string_literal(&node.to_string()))),
+            },
+            Node::Group { node: inner, .. } | Node::Look { node: inner, .. } => {
+                call(node.method().unwrap(), vec![Arg::Builder(self.builder(inner))])
+            }
+            // Flags can only be enabled through the builder.
+            Node::Flags { flags, .. } if !flags.disabled.is_empty() => {
+                call("raw", code(string_literal(&node.to_string())))
+            }
+            Node::Flags { flags, node: inner } if node.method() == Some("with_flags") => {
+                let flags: Vec<String> = flags.enabled.iter().map(|flag| format!("Flag::{:?}", flag)).collect();
+                call(
+                    "with_flags",
+                    vec![Arg::Code(format!("[{}]", flags.join(", "))), Arg::Builder(self.builder(inner))],
+                )
+            }
+            Node::Flags { node: inner, .. } => call(node.method().unwrap(), vec![Arg::Builder(self.builder(inner))]),
+            Node::Backreference(n) => call("backreference", code(n.to_string())),
+            Node::Alternation(branches) => {
+                call("any_of", vec![Arg::Builders(branches.iter().map(|branch| self.builder(branch)).collect())])
+            }
+        };
+        builder.calls.push(call);
+    }
+}
+
+/// Quotes text as a Rust string literal, preferring a raw string when it has backslashes.
+fn string_literal(text: &str) -> String {
+    if text.contains('\\') && !text.contains('"') && !text.chars().any(char::is_control) {
+        format!("r\"{}\"", text)
+    } else {
+        format!("{:?}", text)
+    }
+}
+
+/// Returns the expression a builder's chain of calls starts from.
+fn start(builder: &Builder) -> &str {
+    match &builder.variable {
+        Some(variable) if builder.bound => variable,
+        _ => "RegexBuilder::new()",
+    }
+}
+
+fn inline_builder(builder: &Builder) -> String {
+    let mut out = start(builder).to_string();
+    for call in &builder.calls {
+        out.push_str(&inline_call(call));
+    }
+    out
+}
+
+fn inline_call(call: &Call) -> String {
+    let args: Vec<String> = call.args.iter().map(inline_arg).collect();
+    format!(".{}({})", call.method, args.join(", "))
+}
+
+fn inline_arg(arg: &Arg) -> String {
+    match arg {
+        Arg::Code(code) => code.clone(),
+        Arg::Builder(calls) => inline_builder(calls),
+        Arg::Builders(builders) if builders.is_empty() => "[] as [RegexBuilder; 0]".to_string(),
+        Arg::Builders(builders) => {
+            let builders: Vec<String> = builders.iter().map(inline_builder).collect();
+            format!("[{}]", builders.join(", "))
+        }
+    }
+}
+
+/// Writes a builder starting at the current column of a line indented by `indent`.
+fn write_builder(builder: &Builder, indent: usize, out: &mut String) {
+    let inline = inline_builder(builder);
+    let calls = builder.calls.as_slice();
+    // A single call reads best on one line, even if rustfmt would split it.
+    if (calls.len() <= 1 || inline.len() <= CHAIN_WIDTH) && column(out) + inline.len() <= MAX_WIDTH {
+        out.push_str(&inline);
+        return;
+    }
+    out.push_str(start(builder));
+    if let [call] = calls {
+        return write_call(call, indent, out);
+    }
+    for call in calls {
+        newline(indent + 4, out);
+        write_call(call, indent + 4, out);
+    }
+}
+
+/// Writes a call at the end of a line indented by `indent`, splitting its
+/// arguments over several lines when they do not fit.
+fn write_call(call: &Call, indent: usize, out: &mut String) {
+    let inline = inline_call(call);
+    if column(out) + inline.len() <= MAX_WIDTH {
+        return out.push_str(&inline);
+    }
+    out.push('.');
+    out.push_str(call.method);
+    match call.args.as_slice() {
+        // A lone array stays on the line of the call.
+        [Arg::Builders(builders)] => {
+            out.push_str("([");
+            for builder in builders {
+                newline(indent + 4, out);
+                write_builder(builder, indent + 4, out);
+                out.push(',');
+            }
+            newline(indent, out);
+            out.push_str("])");
+        }
+        args => {
+            out.push('(');
+            for arg in args {
+                newline(indent + 4, out);
+                match arg {
+                    Arg::Builder(builder) => write_builder(builder, indent + 4, out),
+                    arg => out.push_str(&inline_arg(arg)),
+                }
+                out.push(',');
+            }
+            newline(indent, out);
+            out.push(')');
+        }
+    }
+}
+
+/// Returns the length of the last line of `out`.
+fn column(out: &str) -> usize {
+    out.len() - out.rfind('\n').map_or(0, |i| i + 1)
+}
+
+fn newline(indent: usize, out: &mut String) {
+    out.push('\n');
+    out.push_str(&" ".repeat(indent));
+}
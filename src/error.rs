@@ -0,0 +1,338 @@
+//! Errors returned when a built pattern fails to compile, a pattern fails to parse,
+//! or captures fail to convert into a struct.
+
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+use crate::ast::{self, GroupKind, Node, Span};
+use crate::backend::{Backend, Unsupported};
+
+/// Error returned by `RegexBuilder::to_regex` and `RegexBuilder::compile` when the built
+/// pattern does not compile, or uses a construct the chosen backend does not support.
+///
+/// Besides the error reported by the backend (available through
+/// [`Error::source`]), it records which node of the
+/// builder produced the offending part of the pattern, and which builder
+/// method and arguments appended that node. Its `Display` output shows the
+/// generated pattern with that part underlined.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::RegexBuilder;
+///
+/// let error = RegexBuilder::new()
+///     .digit()
+///     .positive_lookbehind(RegexBuilder::new().character_class("a"))
+///     .to_regex()
+///     .unwrap_err();
+/// assert_eq!(error.pattern(), "\\d(?<=[a])");
+/// assert_eq!(error.span(), Some(2..10));
+/// assert_eq!(error.method(), Some("positive_lookbehind"));
+/// assert_eq!(error.arguments(), Some("/[a]/"));
+/// println!("{}", error);
+/// // look-behind is not supported by regex::Regex
+/// //     \d(?<=[a])
+/// //       ^^^^^^^^ positive_lookbehind(/[a]/)
+/// ```
+#[derive(Debug)]
+pub struct BuildError {
+    pattern: String,
+    message: String,
+    location: Option<Box<Location>>,
+    source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+#[derive(Debug)]
+struct Location {
+    span: Range<usize>,
+    node: Node,
+    method: &'static str,
+    arguments: String,
+}
+
+impl BuildError {
+    fn new(pattern: String, spans: &[Span<'_>], source: Box<dyn Error + Send + Sync>) -> Self {
+        // A program over the size limit has no offending part to point at.
+        let too_big = matches!(source.downcast_ref(), Some(regex::Error::CompiledTooBig(_)));
+        let syntax_error = match regex_syntax::Parser::new().parse(&pattern) {
+            _ if too_big => None,
+            Err(regex_syntax::Error::Parse(error)) => Some((error.kind().to_string(), error.span().start.offset)),
+            Err(regex_syntax::Error::Translate(error)) => Some((error.kind().to_string(), error.span().start.offset)),
+            _ => None,
+        };
+        let Some((message, offset)) = syntax_error else {
+            return BuildError {
+                pattern,
+                message: source.to_string(),
+                location: None,
+                source: Some(source),
+            };
+        };
+        // The innermost node covering the error is the one that produced it.
+        let location = spans
+            .iter()
+            .filter(|span| span.node.method().is_some())
+            .filter(|span| span.range.contains(&offset) || (offset == span.range.end && offset == pattern.len()))
+            .min_by_key(|span| span.range.len())
+            .map(Location::new);
+        BuildError {
+            pattern,
+            message,
+            location,
+            source: Some(source),
+        }
+    }
+
+    fn unsupported(pattern: String, spans: &[Span<'_>], unsupported: &Unsupported<'_>, backend: &str) -> Self {
+        let message = format!("{} is not supported by {}", unsupported.construct, backend);
+        BuildError::at(pattern, spans, unsupported.node, message)
+    }
+
+    /// Creates an error about `node`, found before the pattern is compiled.
+    fn at(pattern: String, spans: &[Span<'_>], node: &Node, message: String) -> Self {
+        let location = spans.iter().find(|span| std::ptr::eq(span.node, node)).map(Location::new);
+        BuildError {
+            pattern,
+            message,
+            location,
+            source: None,
+        }
+    }
+
+    /// Returns the generated pattern that failed to compile.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns a description of the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the byte range of the pattern produced by the offending node.
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.location.as_ref().map(|location| location.span.clone())
+    }
+
+    /// Returns the node that produced the offending part of the pattern.
+    pub fn node(&self) -> Option<&Node> {
+        self.location.as_ref().map(|location| &location.node)
+    }
+
+    /// Returns the name of the builder method that appended the offending node.
+    pub fn method(&self) -> Option<&str> {
+        self.location.as_ref().map(|location| location.method)
+    }
+
+    /// Returns the arguments of the builder method that appended the offending node,
+    /// as described by [`Node::arguments`].
+    pub fn arguments(&self) -> Option<&str> {
+        self.location.as_ref().map(|location| location.arguments.as_str())
+    }
+}
+
+impl Location {
+    fn new(span: &Span<'_>) -> Box<Location> {
+        Box::new(Location {
+            span: span.range.clone(),
+            node: span.node.clone(),
+            method: span.node.method().unwrap_or("new"),
+            arguments: span.node.arguments(),
+        })
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.message)?;
+        write!(f, "    {}", self.pattern)?;
+        if let Some(location) = &self.location {
+            underline(f, &self.pattern, &location.span)?;
+            write!(f, " {}({})", location.method, location.arguments)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a line of carets under the given span of a pattern written on the previous line.
+fn underline(f: &mut fmt::Formatter<'_>, pattern: &str, span: &Range<usize>) -> fmt::Result {
+    let column = pattern[..span.start].chars().count();
+    let width = pattern[span.clone()].chars().count().max(1);
+    write!(f, "\n    {}{}", " ".repeat(column), "^".repeat(width))
+}
+
+impl Error for BuildError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_ref().map(|source| source.as_ref() as &(dyn Error + 'static))
+    }
+}
+
+/// Error returned by `RegexBuilder::parse` when a pattern is not valid regex syntax.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::RegexBuilder;
+///
+/// let error = RegexBuilder::parse("a(b|c").unwrap_err();
+/// assert_eq!(error.message(), "unclosed group");
+/// assert_eq!(error.span(), 1..2);
+/// println!("{}", error);
+/// // unclosed group
+/// //     a(b|c
+/// //      ^
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pattern: String,
+    message: String,
+    span: Range<usize>,
+}
+
+impl ParseError {
+    pub(crate) fn new(pattern: String, message: String, span: Range<usize>) -> Self {
+        let span = span.start.min(pattern.len())..span.end.min(pattern.len());
+        ParseError { pattern, message, span }
+    }
+
+    /// Returns the pattern that failed to parse.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns a description of the syntax error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the byte range of the pattern where the syntax error is.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.message)?;
+        write!(f, "    {}", self.pattern)?;
+        underline(f, &self.pattern, &self.span)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Error returned by `FromCaptures::from_captures` when a capture group is missing,
+/// or its text does not parse into the type of the field it maps to.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::RegexBuilder;
+///
+/// let (builder, port) = RegexBuilder::new()
+///     .named_group("port", RegexBuilder::new().one_or_more(RegexBuilder::new().word_character()));
+/// let regex = builder.to_regex().unwrap();
+/// let captures = regex.captures("http").unwrap();
+/// let error = port.parse::<u16>(&captures).unwrap_err();
+/// assert_eq!(error.group(), "port");
+/// assert_eq!(error.text(), Some("http"));
+/// assert_eq!(error.to_string(), "cannot parse \"http\" from capture group `port`: invalid digit found in string");
+/// ```
+#[derive(Debug)]
+pub struct CaptureError {
+    group: String,
+    text: Option<String>,
+    source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+impl CaptureError {
+    pub(crate) fn missing(group: &str) -> Self {
+        CaptureError {
+            group: group.to_string(),
+            text: None,
+            source: None,
+        }
+    }
+
+    pub(crate) fn parse(group: &str, text: &str, source: Box<dyn Error + Send + Sync>) -> Self {
+        CaptureError {
+            group: group.to_string(),
+            text: Some(text.to_string()),
+            source: Some(source),
+        }
+    }
+
+    /// Returns the name or index of the capture group that failed.
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    /// Returns the text of the group that failed to parse, or `None` if the group is missing.
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    /// Returns `true` if the group did not participate in the match.
+    pub fn is_missing(&self) -> bool {
+        self.text.is_none()
+    }
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.text, &self.source) {
+            (Some(text), Some(source)) => {
+                write!(f, "cannot parse {:?} from capture group `{}`: {}", text, self.group, source)
+            }
+            _ => write!(f, "capture group `{}` did not participate in the match", self.group),
+        }
+    }
+}
+
+impl Error for CaptureError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_ref().map(|source| source.as_ref() as &(dyn Error + 'static))
+    }
+}
+
+pub(crate) fn compile<B: Backend>(nodes: &[Node]) -> Result<B, BuildError> {
+    let mut spans = Vec::new();
+    let pattern = ast::render(nodes, Some(&mut spans));
+    if let Some((node, message)) = misnamed(nodes) {
+        return Err(BuildError::at(pattern, &spans, node, message));
+    }
+    let unsupported: Vec<Unsupported<'_>> = nodes.iter().flat_map(B::unsupported).collect();
+    if let Some(unsupported) = unsupported.first() {
+        return Err(BuildError::unsupported(pattern, &spans, unsupported, B::name()));
+    }
+    B::compile(nodes, &pattern).map_err(|error| BuildError::new(pattern, &spans, Box::new(error)))
+}
+
+/// Finds a named group that reuses the name of an earlier group, or a backreference
+/// to a name that no group has.
+fn misnamed(nodes: &[Node]) -> Option<(&Node, String)> {
+    let mut names = Vec::new();
+    let mut found = None;
+    nodes.iter().for_each(|node| {
+        node.walk(&mut |node| {
+            if let Node::Group { kind: GroupKind::Named(name), .. } = node {
+                if found.is_none() && names.contains(&name) {
+                    found = Some((node, format!("duplicate capture group name `{}`", name)));
+                }
+                names.push(name);
+            }
+        })
+    });
+    nodes.iter().for_each(|node| {
+        node.walk(&mut |node| {
+            if let Node::NamedBackreference(name) = node {
+                if found.is_none() && !names.contains(&name) {
+                    found = Some((node, format!("backreference to unknown capture group `{}`", name)));
+                }
+            }
+        })
+    });
+    found
+}
@@ -0,0 +1,228 @@
+//! Plain-English descriptions of expression trees.
+
+use crate::ast::{Anchor, Class, ClassItem, Flag, GroupKind, LookKind, Node, PerlClass, Repetition};
+
+/// Describes the given top-level nodes, one line per node, indenting the
+/// nodes that belong to another.
+pub(crate) fn explain(nodes: &[Node]) -> String {
+    let mut explainer = Explainer {
+        lines: Vec::new(),
+        group: 0,
+        dot_all: false,
+    };
+    match nodes {
+        [] => explainer.lines.push("nothing (new)".to_string()),
+        [Node::Alternation(branches)] => explainer.branches(branches, ""),
+        nodes => explainer.sequence(nodes, "", ""),
+    }
+    explainer.lines.join("\n")
+}
+
+struct Explainer {
+    lines: Vec<String>,
+    /// The number of capturing groups seen so far.
+    group: usize,
+    /// Whether `.` matches line breaks at the current node.
+    dot_all: bool,
+}
+
+/// What a node contains, explained on the lines below its own.
+enum Children<'a> {
+    None,
+    Sequence(&'a Node),
+    Branches(&'a [Node]),
+}
+
+impl Explainer {
+    /// Explains nodes matched one after another. The first line starts with
+    /// `bullet`, and the following ones are aligned with it.
+    fn sequence(&mut self, nodes: &[Node], indent: &str, bullet: &str) {
+        let mut nodes = nodes.iter().filter(|node| **node != Node::Empty).peekable();
+        if nodes.peek().is_none() {
+            self.lines.push(format!("{}{}nothing", indent, bullet));
+        }
+        for (i, node) in nodes.enumerate() {
+            let lead = if i == 0 {
+                bullet.to_string()
+            } else {
+                format!("{}then ", " ".repeat(bullet.len()))
+            };
+            self.node(node, indent, &lead, bullet.len());
+        }
+    }
+
+    fn branches(&mut self, branches: &[Node], indent: &str) {
+        if branches.is_empty() {
+            self.lines.push(format!("{}nothing can match", indent));
+        }
+        for branch in branches {
+            self.sequence(as_slice(branch), indent, "- ");
+        }
+    }
+
+    fn node(&mut self, node: &Node, indent: &str, lead: &str, bullet: usize) {
+        let dot_all = self.dot_all;
+        let (text, children) = self.describe(node);
+        let method = node.method().map(|method| format!(" ({})", method)).unwrap_or_default();
+        let colon = if matches!(children, Children::None) { "" } else { ":" };
+        self.lines.push(format!("{}{}{}{}{}", indent, lead, text, method, colon));
+        let indent = format!("{}{}  ", indent, " ".repeat(bullet));
+        match children {
+            Children::None => {}
+            Children::Sequence(Node::Alternation(branches)) => self.branches(branches, &indent),
+            Children::Sequence(node) => self.sequence(as_slice(node), &indent, ""),
+            Children::Branches(branches) => self.branches(branches, &indent),
+        }
+        self.dot_all = dot_all;
+    }
+
+    /// Returns the description of a node and what it contains. Flags the node
+    /// sets are applied to the explainer, for the duration of its children.
+    fn describe<'a>(&mut self, node: &'a Node) -> (String, Children<'a>) {
+        let text = match node {
+            Node::Empty => "nothing".to_string(),
+            Node::Raw(text) => format!("the pattern `{}`", text),
+            Node::Literal(c) => format!("the character {:?}", c),
+            Node::Text(text) => format!("the text {:?}", text),
+            Node::Dot if self.dot_all => "any character".to_string(),
+            Node::Dot => "any character except a line break".to_string(),
+            Node::Anchor(anchor) => match anchor {
+                Anchor::StartOfLine => "start of line",
+                Anchor::EndOfLine => "end of line",
+                Anchor::StartOfText => "start of text",
+                Anchor::EndOfText => "end of text",
+                Anchor::WordBoundary => "a word boundary",
+                Anchor::NonWordBoundary => "a position that is not a word boundary",
+            }
+            .to_string(),
+            Node::Class(Class::Perl(class)) => perl_class(*class).to_string(),
+            Node::Class(Class::Set(set)) => {
+                let items: Vec<String> = set.items.iter().map(class_item).collect();
+                match (set.negated, items.as_slice()) {
+                    (false, [item]) => match &set.items[0] {
+                        ClassItem::Char(_) => format!("the character {}", item),
+                        ClassItem::Range(_, _) => format!("a character from {}", item),
+                        _ => item.clone(),
+                    },
+                    (false, _) => format!("one of {}", list(&items, "or")),
+                    (true, _) => format!("any character except {}", list(&items, "or")),
+                }
+            }
+            Node::Repeat { node: inner, repetition, greedy } => {
+                let mut text = match repetition {
+                    Repetition::ZeroOrMore => "zero or more times".to_string(),
+                    Repetition::OneOrMore => "one or more times".to_string(),
+                    Repetition::ZeroOrOne => "optionally".to_string(),
+                    Repetition::Exactly(n) => format!("exactly {} times", n),
+                    Repetition::AtLeast(n) => format!("at least {} times", n),
+                    Repetition::Between(n, m) => format!("between {} and {} times", n, m),
+                };
+                if !greedy {
+                    text.push_str(", as few as possible");
+                }
+                // `optional` wraps its argument in a capturing group.
+                match (node.method(), inner.as_ref()) {
+                    (Some("optional"), Node::Group { node: inner, .. }) => {
+                        self.group += 1;
+                        text.push_str(&format!(", as capture group {}", self.group));
+                        return (text, Children::Sequence(inner));
+                    }
+                    _ => return (text, Children::Sequence(inner)),
+                }
+            }
+            Node::Group { kind, node } => {
+                let text = match kind {
+                    GroupKind::NonCapturing => "a group".to_string(),
+                    GroupKind::Capturing => {
+                        self.group += 1;
+                        format!("capture group {}", self.group)
+                    }
+                    GroupKind::Named(name) => {
+                        self.group += 1;
+                        format!("capture group {} named {:?}", self.group, name)
+                    }
+                };
+                return (text, Children::Sequence(node));
+            }
+            Node::Flags { flags, node } => {
+                let mut parts = Vec::new();
+                if !flags.enabled.is_empty() {
+                    let enabled: Vec<String> = flags.enabled.iter().map(|flag| flag_name(*flag).to_string()).collect();
+                    parts.push(format!("with {}", list(&enabled, "and")));
+                }
+                if !flags.disabled.is_empty() {
+                    let disabled: Vec<String> = flags.disabled.iter().map(|flag| flag_name(*flag).to_string()).collect();
+                    parts.push(format!("without {}", list(&disabled, "or")));
+                }
+                if flags.enabled.contains(&Flag::DotMatchesNewLine) {
+                    self.dot_all = true;
+                }
+                if flags.disabled.contains(&Flag::DotMatchesNewLine) {
+                    self.dot_all = false;
+                }
+                return (parts.join(" and "), Children::Sequence(node));
+            }
+            Node::Look { kind, node } => {
+                let text = match kind {
+                    LookKind::PositiveLookahead => "followed by",
+                    LookKind::NegativeLookahead => "not followed by",
+                    LookKind::PositiveLookbehind => "preceded by",
+                    LookKind::NegativeLookbehind => "not preceded by",
+                };
+                return (text.to_string(), Children::Sequence(node));
+            }
+            Node::Backreference(n) => format!("the same text as capture group {}", n),
+            Node::NamedBackreference(name) => format!("the same text as the capture group named {:?}", name),
+            Node::Alternation(branches) => return ("one of".to_string(), Children::Branches(branches)),
+            Node::Concat(_) => "a sequence".to_string(),
+        };
+        (text, Children::None)
+    }
+}
+
+fn as_slice(node: &Node) -> &[Node] {
+    match node {
+        Node::Concat(nodes) => nodes,
+        node => std::slice::from_ref(node),
+    }
+}
+
+fn perl_class(class: PerlClass) -> &'static str {
+    match class {
+        PerlClass::Digit => "a digit",
+        PerlClass::NonDigit => "a non-digit",
+        PerlClass::Word => "a word character",
+        PerlClass::NonWord => "a non-word character",
+        PerlClass::Whitespace => "a whitespace character",
+        PerlClass::NonWhitespace => "a non-whitespace character",
+    }
+}
+
+fn class_item(item: &ClassItem) -> String {
+    match item {
+        ClassItem::Char(c) => format!("{:?}", c),
+        ClassItem::Range(start, end) => format!("{:?} to {:?}", start, end),
+        ClassItem::Perl(class) => perl_class(*class).to_string(),
+        ClassItem::Ascii(name) => format!("an ASCII {} character", name),
+    }
+}
+
+fn flag_name(flag: Flag) -> &'static str {
+    match flag {
+        Flag::CaseInsensitive => "case-insensitive matching",
+        Flag::MultiLine => "multi-line anchors",
+        Flag::DotMatchesNewLine => "`.` matching line breaks",
+        Flag::IgnoreWhitespace => "whitespace ignored",
+        Flag::SwapGreed => "lazy quantifiers by default",
+        Flag::Crlf => "CRLF line terminators",
+    }
+}
+
+/// Joins items as `a, b or c`.
+fn list(items: &[String], conjunction: &str) -> String {
+    match items {
+        [] => String::new(),
+        [item] => item.clone(),
+        [init @ .., last] => format!("{} {} {}", init.join(", "), conjunction, last),
+    }
+}
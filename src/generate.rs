@@ -0,0 +1,358 @@
+//! Random strings that match a pattern, for fuzzing and property tests.
+//!
+//! A [`Generator`] walks the expression tree of a `RegexBuilder` and picks a
+//! random path through it: one branch of each alternation, a number of
+//! repetitions within the bounds of each quantifier, and one character of each
+//! class, with case folding and other flags taken into account. Backreferences
+//! repeat the text their group produced. Anchors, word boundaries and
+//! look-around assertions produce no text; when a pattern has any, each string
+//! is checked against the pattern and generated again if it does not match.
+//!
+//! Requires the `rand` feature.
+
+use rand::Rng;
+
+use crate::ast::{Anchor, GroupKind, Node, Repetition};
+use crate::backtrack::{self, Context, Matcher};
+use crate::{parse, RegexBuilder};
+
+/// The number of repetitions an unbounded quantifier may add to its minimum unless
+/// configured otherwise.
+pub const DEFAULT_REPEAT_LIMIT: usize = 8;
+
+/// The number of strings generated for a single call unless configured otherwise.
+pub const DEFAULT_ATTEMPTS: usize = 100;
+
+/// The characters preferred when a class has any of them.
+const PRINTABLE_ASCII: (char, char) = (' ', '~');
+
+/// Generator of random strings matching a pattern.
+///
+/// # Example
+///
+/// ```
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use simple_regex::RegexBuilder;
+///
+/// let builder = RegexBuilder::new()
+///     .start_of_line()
+///     .one_or_more(RegexBuilder::new().range_character_class('a', 'z'))
+///     .literal('=')
+///     .min_repetitions(RegexBuilder::new().digit(), 1)
+///     .end_of_line();
+/// let generator = builder.generator().with_repeat_limit(3);
+/// let regex = builder.to_regex().unwrap();
+/// let mut rng = StdRng::seed_from_u64(7);
+/// for _ in 0..100 {
+///     let text = generator.generate(&mut rng).unwrap();
+///     assert!(regex.is_match(&text), "{:?}", text);
+///     let (key, value) = text.split_once('=').unwrap();
+///     assert!((1..=4).contains(&key.len()) && (1..=4).contains(&value.len()), "{:?}", text);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Generator {
+    part: Part,
+    groups: usize,
+    repeat_limit: usize,
+    attempts: usize,
+    full_unicode: bool,
+    /// The pattern that generated strings are checked against, if they need to be.
+    check: Option<Matcher>,
+}
+
+/// A node of the expression tree, with classes resolved to ranges.
+#[derive(Clone, Debug)]
+enum Part {
+    Text(String),
+    Class {
+        ranges: Box<[(char, char)]>,
+        printable: Box<[(char, char)]>,
+    },
+    Repeat {
+        part: Box<Part>,
+        min: usize,
+        max: Option<usize>,
+    },
+    Group(usize, Box<Part>),
+    Backreference(usize),
+    Alternation(Vec<Part>),
+    Concat(Vec<Part>),
+}
+
+impl Generator {
+    /// Creates a generator for the pattern of the given builder.
+    ///
+    /// Raw pattern text is read one `raw` call at a time, so text that only forms a
+    /// pattern together with its neighbours, such as an unclosed group, generates nothing.
+    pub fn new(builder: &RegexBuilder) -> Self {
+        let names = parse::group_names(builder.nodes());
+        let mut lowering = Lowering { names: &names, next: 1 };
+        let parts = builder.nodes().iter().map(|node| lowering.part(node, Context::default()));
+        let mut part = Part::Concat(parts.collect());
+        let mut needs_check = false;
+        for node in builder.nodes() {
+            node.walk(&mut |node| {
+                needs_check |= matches!(node, Node::Anchor(_) | Node::Look { .. } | Node::Raw(_));
+            });
+        }
+        let mut check = None;
+        if needs_check {
+            let whole = RegexBuilder {
+                nodes: vec![
+                    Node::Anchor(Anchor::StartOfText),
+                    Node::Group {
+                        kind: GroupKind::NonCapturing,
+                        // The backtracking engine needs the structure of raw pattern text.
+                        node: Box::new(Node::concat(builder.nodes().iter().map(expand_raw).collect())),
+                    },
+                    Node::Anchor(Anchor::EndOfText),
+                ],
+            };
+            match whole.to_matcher() {
+                Ok(matcher) => check = Some(matcher),
+                // Nothing matches a pattern that does not compile.
+                Err(_) => part = Part::Alternation(Vec::new()),
+            }
+        }
+        Generator {
+            part,
+            groups: names.len() + 1,
+            repeat_limit: DEFAULT_REPEAT_LIMIT,
+            attempts: DEFAULT_ATTEMPTS,
+            full_unicode: false,
+            check,
+        }
+    }
+
+    /// Returns a copy of the generator that repeats unbounded quantifiers, such as
+    /// `zero_or_more` and `min_repetitions`, at most `limit` times beyond their minimum.
+    pub fn with_repeat_limit(mut self, limit: usize) -> Self {
+        self.repeat_limit = limit;
+        self
+    }
+
+    /// Returns a copy of the generator that generates at most `attempts` strings per
+    /// call before giving up on a pattern whose assertions keep failing.
+    pub fn with_attempts(mut self, attempts: usize) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// Returns a copy of the generator that draws characters from the whole of each
+    /// class. By default, characters are drawn from the printable ASCII characters
+    /// of a class when it has any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let builder = RegexBuilder::new().exact_repetitions(RegexBuilder::new().dot(), 50);
+    /// let mut rng = StdRng::seed_from_u64(1);
+    /// assert!(builder.generator().generate(&mut rng).unwrap().is_ascii());
+    /// assert!(!builder.generator().with_full_unicode(true).generate(&mut rng).unwrap().is_ascii());
+    /// ```
+    pub fn with_full_unicode(mut self, yes: bool) -> Self {
+        self.full_unicode = yes;
+        self
+    }
+
+    /// Returns a random string matched by the whole pattern, or `None` if none was
+    /// found within the configured number of attempts, as happens for patterns that
+    /// cannot match anything.
+    pub fn generate<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<String> {
+        for _ in 0..self.attempts {
+            let mut out = String::new();
+            let mut captures = vec![None; self.groups];
+            if !self.emit(&self.part, rng, &mut out, &mut captures) {
+                continue;
+            }
+            if self.check.as_ref().is_none_or(|matcher| matcher.is_match(&out)) {
+                return Some(out);
+            }
+        }
+        None
+    }
+
+    /// Appends a random string matched by `part`, returning `false` if there is none.
+    fn emit<R: Rng + ?Sized>(&self, part: &Part, rng: &mut R, out: &mut String, captures: &mut [Option<String>]) -> bool {
+        match part {
+            Part::Text(text) => out.push_str(text),
+            Part::Class { ranges, printable } => {
+                let ranges = if self.full_unicode || printable.is_empty() { ranges } else { printable };
+                match pick(ranges, rng) {
+                    Some(c) => out.push(c),
+                    None => return false,
+                }
+            }
+            Part::Repeat { part, min, max } => {
+                let max = max.unwrap_or(min.saturating_add(self.repeat_limit)).max(*min);
+                for _ in 0..rng.gen_range(*min..=max) {
+                    if !self.emit(part, rng, out, captures) {
+                        return false;
+                    }
+                }
+            }
+            Part::Group(index, part) => {
+                let start = out.len();
+                if !self.emit(part, rng, out, captures) {
+                    return false;
+                }
+                captures[*index] = Some(out[start..].to_string());
+            }
+            Part::Backreference(index) => match captures.get(*index) {
+                Some(Some(text)) => out.push_str(text),
+                // A group that did not participate is never matched again.
+                _ => return false,
+            },
+            Part::Alternation(branches) => {
+                if branches.is_empty() {
+                    return false;
+                }
+                let branch = &branches[rng.gen_range(0..branches.len())];
+                return self.emit(branch, rng, out, captures);
+            }
+            Part::Concat(parts) => {
+                return parts.iter().all(|part| self.emit(part, rng, out, captures));
+            }
+        }
+        true
+    }
+}
+
+/// Picks a random character from the given ranges.
+fn pick<R: Rng + ?Sized>(ranges: &[(char, char)], rng: &mut R) -> Option<char> {
+    let total: u32 = ranges.iter().map(|&(start, end)| end as u32 - start as u32 + 1).sum();
+    if total == 0 {
+        return None;
+    }
+    loop {
+        let mut offset = rng.gen_range(0..total);
+        for &(start, end) in ranges {
+            let size = end as u32 - start as u32 + 1;
+            if offset < size {
+                // Ranges may span the surrogates, which are not characters.
+                if let Some(c) = char::from_u32(start as u32 + offset) {
+                    return Some(c);
+                }
+                break;
+            }
+            offset -= size;
+        }
+    }
+}
+
+/// Returns a copy of `node` with raw pattern text replaced by its tree, where it parses.
+fn expand_raw(node: &Node) -> Node {
+    match node {
+        Node::Raw(text) => parse::parse_raw(text).map_or_else(|| node.clone(), |node| expand_raw(&node)),
+        Node::Repeat { node, repetition, greedy } => Node::Repeat {
+            node: Box::new(expand_raw(node)),
+            repetition: *repetition,
+            greedy: *greedy,
+        },
+        Node::Group { kind, node } => Node::Group {
+            kind: kind.clone(),
+            node: Box::new(expand_raw(node)),
+        },
+        Node::Flags { flags, node } => Node::Flags {
+            flags: flags.clone(),
+            node: Box::new(expand_raw(node)),
+        },
+        Node::Look { kind, node } => Node::Look {
+            kind: *kind,
+            node: Box::new(expand_raw(node)),
+        },
+        Node::Alternation(nodes) => Node::Alternation(nodes.iter().map(expand_raw).collect()),
+        Node::Concat(nodes) => Node::Concat(nodes.iter().map(expand_raw).collect()),
+        node => node.clone(),
+    }
+}
+
+/// Turns the expression tree into [`Part`]s.
+struct Lowering<'a> {
+    /// The names of the capturing groups, as returned by `parse::group_names`.
+    names: &'a [Option<String>],
+    /// The index of the next capturing group.
+    next: usize,
+}
+
+impl Lowering<'_> {
+    fn part(&mut self, node: &Node, context: Context) -> Part {
+        match node {
+            Node::Empty | Node::Anchor(_) | Node::Look { .. } => Part::Concat(Vec::new()),
+            Node::Literal(c) => self.class(&Node::Literal(*c).to_string(), context),
+            Node::Text(text) => Part::Concat(
+                text.chars()
+                    .map(|c| self.class(&Node::Literal(c).to_string(), context))
+                    .collect(),
+            ),
+            Node::Dot | Node::Class(_) => self.class(&node.to_string(), context),
+            Node::Raw(text) => match parse::parse_raw(text) {
+                Some(node) => self.part(&node, context),
+                // Text the parser leaves raw, such as `\p{Greek}`, is usually a class.
+                None => self.class(text, context),
+            },
+            Node::Repeat { node, repetition, .. } => {
+                let (min, max) = match *repetition {
+                    Repetition::ZeroOrMore => (0, None),
+                    Repetition::OneOrMore => (1, None),
+                    Repetition::ZeroOrOne => (0, Some(1)),
+                    Repetition::Exactly(n) => (n, Some(n)),
+                    Repetition::AtLeast(n) => (n, None),
+                    Repetition::Between(n, m) => (n, Some(m)),
+                };
+                Part::Repeat {
+                    part: Box::new(self.part(node, context)),
+                    min,
+                    max,
+                }
+            }
+            Node::Group { kind, node: inner } => match kind {
+                GroupKind::Capturing | GroupKind::Named(_) => {
+                    let index = self.next;
+                    self.next += 1;
+                    Part::Group(index, Box::new(self.part(inner, context)))
+                }
+                GroupKind::NonCapturing => self.part(inner, context),
+            },
+            Node::Flags { flags, node } => {
+                let mut context = context;
+                flags.enabled.iter().for_each(|flag| context.set(*flag, true));
+                flags.disabled.iter().for_each(|flag| context.set(*flag, false));
+                self.part(node, context)
+            }
+            Node::Backreference(group) => Part::Backreference(*group),
+            Node::NamedBackreference(name) => {
+                let index = self.names.iter().position(|group| group.as_deref() == Some(name));
+                // Group 0 is never recorded, so an unknown name never matches.
+                Part::Backreference(index.map_or(0, |index| index + 1))
+            }
+            Node::Alternation(branches) => {
+                Part::Alternation(branches.iter().map(|branch| self.part(branch, context)).collect())
+            }
+            Node::Concat(nodes) => Part::Concat(nodes.iter().map(|node| self.part(node, context)).collect()),
+        }
+    }
+
+    fn class(&self, syntax: &str, context: Context) -> Part {
+        let ranges = backtrack::class_ranges(&context.scope(syntax)).unwrap_or_default();
+        if let [(start, end)] = *ranges {
+            if start == end {
+                return Part::Text(start.to_string());
+            }
+        }
+        let printable = ranges
+            .iter()
+            .filter_map(|&(start, end)| {
+                let (start, end) = (start.max(PRINTABLE_ASCII.0), end.min(PRINTABLE_ASCII.1));
+                (start <= end).then_some((start, end))
+            })
+            .collect();
+        Part::Class { ranges, printable }
+    }
+}
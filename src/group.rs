@@ -0,0 +1,142 @@
+//! Handles to named capturing groups, and conversion of captures into structs.
+
+use std::error::Error;
+use std::str::FromStr;
+
+use regex::Captures;
+
+use crate::error::CaptureError;
+
+/// A handle to a named capturing group, returned by `RegexBuilder::named_group`
+/// when the group is appended, or by `RegexBuilder::named_group_handle` later on.
+///
+/// The handle refers to the group by name rather than by index, so backreferences
+/// and extraction code keep working when groups are added or reordered.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::RegexBuilder;
+///
+/// let word = RegexBuilder::new().one_or_more(RegexBuilder::new().word_character());
+/// let (builder, group) = RegexBuilder::new()
+///     .capturing_group(RegexBuilder::new().digit())
+///     .named_group("word", word);
+///
+/// let regex = builder.to_regex().unwrap();
+/// let captures = regex.captures("1hello").unwrap();
+/// assert_eq!(group.as_str(&captures), Some("hello"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Group {
+    name: String,
+}
+
+impl Group {
+    /// Creates a handle to the capturing group with the given name.
+    pub(crate) fn new(name: &str) -> Self {
+        Group { name: name.to_string() }
+    }
+
+    /// Returns the name of the group.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the match of the group in `captures`, if it participated in the match.
+    pub fn get<'h>(&self, captures: &Captures<'h>) -> Option<regex::Match<'h>> {
+        captures.name(&self.name)
+    }
+
+    /// Returns the text matched by the group in `captures`, if it participated in the match.
+    pub fn as_str<'h>(&self, captures: &Captures<'h>) -> Option<&'h str> {
+        self.get(captures).map(|m| m.as_str())
+    }
+
+    /// Parses the text matched by the group in `captures` with `FromStr`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CaptureError`] if the group did not participate in the match,
+    /// or if its text does not parse.
+    pub fn parse<T>(&self, captures: &Captures<'_>) -> Result<T, CaptureError>
+    where
+        T: FromStr,
+        T::Err: Error + Send + Sync + 'static,
+    {
+        parse_group(captures, &self.name)
+    }
+
+    /// Parses the text matched by the group in `captures` like [`Group::parse`],
+    /// returning `None` if the group did not participate in the match.
+    pub fn parse_optional<T>(&self, captures: &Captures<'_>) -> Result<Option<T>, CaptureError>
+    where
+        T: FromStr,
+        T::Err: Error + Send + Sync + 'static,
+    {
+        parse_optional_group(captures, &self.name)
+    }
+}
+
+/// Types that can be built from the named groups of a match.
+///
+/// With the `derive` feature, `#[derive(FromCaptures)]` implements the trait for
+/// structs with named fields. Each field is parsed with `FromStr` from the group of
+/// the same name, or from the group named by a `#[group("name")]` attribute, and
+/// fields of type `Option<T>` are `None` when their group did not participate in
+/// the match.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{parse_group, parse_optional_group, CaptureError, FromCaptures, RegexBuilder};
+/// use regex::Captures;
+///
+/// struct Size {
+///     width: u32,
+///     height: Option<u32>,
+/// }
+///
+/// impl FromCaptures for Size {
+///     fn from_captures(captures: &Captures<'_>) -> Result<Self, CaptureError> {
+///         Ok(Size {
+///             width: parse_group(captures, "width")?,
+///             height: parse_optional_group(captures, "height")?,
+///         })
+///     }
+/// }
+///
+/// let number = || RegexBuilder::new().one_or_more(RegexBuilder::new().digit());
+/// let (builder, _) = RegexBuilder::new().named_group("width", number());
+/// let (height, _) = RegexBuilder::new().literal('x').named_group("height", number());
+/// let regex = builder.optional(height).to_regex().unwrap();
+/// let size = Size::from_captures(&regex.captures("640x480").unwrap()).unwrap();
+/// assert_eq!((size.width, size.height), (640, Some(480)));
+/// let size = Size::from_captures(&regex.captures("640").unwrap()).unwrap();
+/// assert_eq!((size.width, size.height), (640, None));
+/// ```
+pub trait FromCaptures: Sized {
+    /// Builds a value from the capture groups of a match.
+    fn from_captures(captures: &Captures<'_>) -> Result<Self, CaptureError>;
+}
+
+/// Parses the group named `name`, which must have participated in the match.
+pub fn parse_group<T>(captures: &Captures<'_>, name: &str) -> Result<T, CaptureError>
+where
+    T: FromStr,
+    T::Err: Error + Send + Sync + 'static,
+{
+    parse_optional_group(captures, name)?.ok_or_else(|| CaptureError::missing(name))
+}
+
+/// Parses the group named `name`, if it participated in the match.
+pub fn parse_optional_group<T>(captures: &Captures<'_>, name: &str) -> Result<Option<T>, CaptureError>
+where
+    T: FromStr,
+    T::Err: Error + Send + Sync + 'static,
+{
+    captures
+        .name(name)
+        .map(|m| m.as_str().parse().map_err(|error| CaptureError::parse(name, m.as_str(), Box::new(error))))
+        .transpose()
+}
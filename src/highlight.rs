@@ -0,0 +1,253 @@
+//! Syntax highlighting of rendered patterns, and of matches in text, with the `ansi` module.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::ansi::Color;
+use crate::ast::{self, Node, Span};
+use crate::parse;
+
+/// Colors of matching parentheses, by nesting depth.
+const GROUP_COLORS: [Color; 6] = [
+    Color::Yellow,
+    Color::Blue,
+    Color::Purple,
+    Color::Cyan,
+    Color::Green,
+    Color::Red,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Literal,
+    Escape,
+    Class,
+    Anchor,
+    Quantifier,
+    /// A parenthesis or `|` at the given nesting depth.
+    Group(usize),
+}
+
+impl Role {
+    fn color(self) -> Color {
+        match self {
+            Role::Literal => Color::White,
+            Role::Escape => Color::Cyan,
+            Role::Class => Color::Green,
+            Role::Anchor => Color::Red,
+            Role::Quantifier => Color::Purple,
+            Role::Group(depth) => GROUP_COLORS[depth % GROUP_COLORS.len()],
+        }
+    }
+}
+
+/// Colors every part of the rendered pattern of `nodes` by the role of the node
+/// that produced it, with each pair of parentheses in a color of its own.
+pub(crate) fn highlight(nodes: &[Node]) -> String {
+    let mut spans = Vec::new();
+    let pattern = ast::render(nodes, Some(&mut spans));
+    let mut painter = Painter::new(&spans, 0);
+    match nodes {
+        // A lone alternation is rendered without a group around it.
+        [node] => painter.paint(node, 0, true),
+        nodes => nodes.iter().for_each(|node| painter.paint(node, 0, false)),
+    }
+    let mut out = String::new();
+    let mut run: Option<(Role, Range<usize>)> = None;
+    for (role, range) in painter.parts {
+        // Neighbouring parts of the same role share one color code.
+        match &mut run {
+            Some((current, current_range)) if *current == role && !matches!(role, Role::Group(_)) => {
+                current_range.end = range.end;
+            }
+            _ => {
+                if let Some((current, current_range)) = run.replace((role, range)) {
+                    write!(out, "{}", current.color().paint(&pattern[current_range])).unwrap();
+                }
+            }
+        }
+    }
+    if let Some((role, range)) = run {
+        write!(out, "{}", role.color().paint(&pattern[range])).unwrap();
+    }
+    out
+}
+
+/// Splits a rendered pattern into parts colored by role, following the tree
+/// that was rendered.
+struct Painter {
+    /// The range of the pattern each node rendered to, by the address of the node.
+    ranges: HashMap<*const Node, Range<usize>>,
+    parts: Vec<(Role, Range<usize>)>,
+}
+
+impl Painter {
+    /// Creates a painter for a pattern whose spans start `offset` bytes into the whole pattern.
+    fn new(spans: &[Span<'_>], offset: usize) -> Self {
+        let ranges = spans
+            .iter()
+            .map(|span| (span.node as *const Node, span.range.start + offset..span.range.end + offset))
+            .collect();
+        Painter { ranges, parts: Vec::new() }
+    }
+
+    fn range(&self, node: &Node) -> Range<usize> {
+        self.ranges[&(node as *const Node)].clone()
+    }
+
+    fn push(&mut self, role: Role, range: Range<usize>) {
+        if !range.is_empty() {
+            self.parts.push((role, range));
+        }
+    }
+
+    /// Paints `node`, nested in `depth` pairs of parentheses. A `bare` alternation
+    /// was rendered without a group of its own.
+    fn paint(&mut self, node: &Node, depth: usize, bare: bool) {
+        let range = self.range(node);
+        match node {
+            Node::Empty => {}
+            Node::Literal(c) => self.literal([*c], range.start),
+            Node::Text(text) => self.literal(text.chars(), range.start),
+            Node::Raw(text) => self.raw(text, range, depth),
+            Node::Dot | Node::Class(_) => self.push(Role::Class, range),
+            Node::Anchor(_) => self.push(Role::Anchor, range),
+            Node::Backreference(_) | Node::NamedBackreference(_) => self.push(Role::Escape, range),
+            Node::Repeat { node: inner, .. } => {
+                let inner_range = self.range(inner);
+                if inner.is_atom() {
+                    self.paint(inner, depth, false);
+                    self.push(Role::Quantifier, inner_range.end..range.end);
+                } else {
+                    // The group written around the repeated sequence, and its closing parenthesis.
+                    self.push(Role::Group(depth), range.start..inner_range.start);
+                    self.paint(inner, depth + 1, true);
+                    self.push(Role::Group(depth), inner_range.end..inner_range.end + 1);
+                    self.push(Role::Quantifier, inner_range.end + 1..range.end);
+                }
+            }
+            Node::Group { node: inner, .. } | Node::Flags { node: inner, .. } | Node::Look { node: inner, .. } => {
+                self.enclose(range, std::slice::from_ref(inner), depth + 1, Role::Group(depth), true);
+            }
+            // An alternation of no branches is rendered as an empty class.
+            Node::Alternation(branches) if branches.is_empty() && bare => self.push(Role::Class, range),
+            Node::Alternation(branches) if branches.is_empty() => {
+                self.push(Role::Group(depth), range.start..range.start + 3);
+                self.push(Role::Class, range.start + 3..range.end - 1);
+                self.push(Role::Group(depth), range.end - 1..range.end);
+            }
+            // The `|` of a bare alternation takes the color of the parentheses around it.
+            Node::Alternation(branches) if bare => {
+                self.enclose(range, branches, depth, Role::Group(depth.saturating_sub(1)), false);
+            }
+            Node::Alternation(branches) => self.enclose(range, branches, depth + 1, Role::Group(depth), false),
+            Node::Concat(nodes) => nodes.iter().for_each(|node| self.paint(node, depth, false)),
+        }
+    }
+
+    /// Paints the children of a node at `depth`, and what the node wrote around them in the color of `role`.
+    fn enclose(&mut self, range: Range<usize>, children: &[Node], depth: usize, role: Role, bare: bool) {
+        let mut pos = range.start;
+        for child in children {
+            let child_range = self.range(child);
+            self.push(role, pos..child_range.start);
+            self.paint(child, depth, bare);
+            pos = child_range.end;
+        }
+        self.push(role, pos..range.end);
+    }
+
+    /// Paints literal characters rendered from `start` on, escaped ones as escapes.
+    fn literal(&mut self, chars: impl IntoIterator<Item = char>, start: usize) {
+        let mut pos = start;
+        for c in chars {
+            let len = Node::Literal(c).to_string().len();
+            let role = if len == c.len_utf8() { Role::Literal } else { Role::Escape };
+            self.push(role, pos..pos + len);
+            pos += len;
+        }
+    }
+
+    /// Paints raw pattern text by the tree it parses into, if that renders back to the same text.
+    fn raw(&mut self, text: &str, range: Range<usize>, depth: usize) {
+        if let Ok(node) = parse::parse(text) {
+            let nodes = [node];
+            let mut spans = Vec::new();
+            if ast::render(&nodes, Some(&mut spans)) == text {
+                let mut painter = Painter::new(&spans, range.start);
+                painter.parts = std::mem::take(&mut self.parts);
+                painter.paint(&nodes[0], depth, true);
+                self.parts = painter.parts;
+                return;
+            }
+        }
+        self.push(Role::Literal, range);
+    }
+}
+
+/// Colors of capture groups in matches, by group index.
+const MATCH_COLORS: [Color; 6] = [
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Purple,
+    Color::Cyan,
+];
+
+fn match_color(group: usize) -> Color {
+    MATCH_COLORS[group % MATCH_COLORS.len()]
+}
+
+/// Colors every match of `regex` in `haystack`, each part of a match in the
+/// color of the innermost capture group covering it.
+pub(crate) fn matches(regex: &regex::Regex, haystack: &str) -> String {
+    let mut out = String::new();
+    let mut last = 0;
+    for captures in regex.captures_iter(haystack) {
+        let whole = captures.get(0).expect("group 0 is always set");
+        out.push_str(&haystack[last..whole.start()]);
+        let groups: Vec<(usize, regex::Match)> = captures
+            .iter()
+            .enumerate()
+            .filter_map(|(index, group)| group.map(|group| (index, group)))
+            .collect();
+        let mut bounds: Vec<usize> = groups.iter().flat_map(|(_, group)| [group.start(), group.end()]).collect();
+        bounds.sort_unstable();
+        bounds.dedup();
+        for piece in bounds.windows(2) {
+            let (start, end) = (piece[0], piece[1]);
+            // Groups are numbered by their opening parenthesis, so the last
+            // group covering a piece is the innermost one.
+            let group = groups
+                .iter()
+                .rev()
+                .find(|(_, group)| group.start() <= start && end <= group.end())
+                .map(|(index, _)| *index)
+                .unwrap_or(0);
+            write!(out, "{}", match_color(group).paint(&haystack[start..end])).unwrap();
+        }
+        last = whole.end();
+    }
+    out.push_str(&haystack[last..]);
+    out
+}
+
+/// Lists the groups of `regex` in the colors used by [`matches`], labelled by
+/// name where they have one, and by index otherwise.
+pub(crate) fn legend(regex: &regex::Regex) -> String {
+    let labels: Vec<String> = regex
+        .capture_names()
+        .enumerate()
+        .map(|(index, name)| {
+            let label = match (index, name) {
+                (0, _) => "match".to_string(),
+                (_, Some(name)) => name.to_string(),
+                (index, None) => index.to_string(),
+            };
+            match_color(index).paint(label).to_string()
+        })
+        .collect();
+    labels.join(" ")
+}
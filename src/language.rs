@@ -0,0 +1,554 @@
+//! The set of strings a pattern matches, for counting and enumerating them.
+//!
+//! A [`Language`] compiles the expression tree of a `RegexBuilder` into a
+//! deterministic automaton over the strings that the whole pattern matches,
+//! with case folding and other flags taken into account. It can then tell how
+//! many such strings there are, or whether there are infinitely many, and list
+//! them in shortlex order: shorter strings first, and strings of the same
+//! length in order of their characters.
+//!
+//! Backreferences, look-around assertions, word boundaries and multi-line
+//! anchors depend on text outside a single string or make the set irregular,
+//! so they are not supported.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::ast::{Anchor, Flag, Node, Repetition};
+use crate::backend::{Backend, Unsupported};
+use crate::backtrack::{self, Context};
+
+/// The number of strings in a [`Language`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Count {
+    /// Exactly this many strings.
+    Finite(u128),
+    /// Finitely many strings, but more than `u128::MAX`.
+    Overflow,
+    /// Infinitely many strings.
+    Infinite,
+}
+
+impl fmt::Display for Count {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Count::Finite(count) => write!(f, "{}", count),
+            Count::Overflow => write!(f, "more than {}", u128::MAX),
+            Count::Infinite => f.write_str("infinitely many"),
+        }
+    }
+}
+
+/// The strings matched by a whole pattern, as a deterministic automaton.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::RegexBuilder;
+/// use simple_regex::language::Count;
+///
+/// let digit = || RegexBuilder::new().range_character_class('0', '9');
+/// let language = RegexBuilder::new().exact_repetitions(digit(), 3).language().unwrap();
+/// assert_eq!(language.count(), Count::Finite(1000));
+/// let strings: Vec<String> = language.strings().collect();
+/// assert_eq!(strings.len(), 1000);
+/// assert_eq!(strings[..3], ["000", "001", "002"]);
+/// assert_eq!(strings.last().unwrap(), "999");
+///
+/// // `\d` matches every Unicode decimal digit, not only the ASCII ones.
+/// let language = RegexBuilder::new().digit().language().unwrap();
+/// assert!(matches!(language.count(), Count::Finite(n) if n > 10));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Language {
+    states: Vec<State>,
+    count: Count,
+    /// The length of the longest string, if there are finitely many.
+    longest: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+struct State {
+    accepting: bool,
+    /// Transitions on disjoint ranges of characters, sorted by character.
+    transitions: Vec<(char, char, usize)>,
+}
+
+impl Language {
+    /// Returns the number of strings in the language.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    /// use simple_regex::language::Count;
+    ///
+    /// let count = |builder: RegexBuilder| builder.language().unwrap().count();
+    /// let answer = RegexBuilder::new().any_of([
+    ///     RegexBuilder::new().string("yes"),
+    ///     RegexBuilder::new().string("no"),
+    /// ]);
+    /// // Besides `s` and `S`, case folding matches the long s, `ſ`.
+    /// assert_eq!(count(RegexBuilder::new().case_insensitive(answer)), Count::Finite(16));
+    /// assert_eq!(count(RegexBuilder::new().one_or_more(RegexBuilder::new().literal('a'))), Count::Infinite);
+    /// assert_eq!(count(RegexBuilder::new().start_of_line().literal('a').start_of_line()), Count::Finite(0));
+    /// assert_eq!(count(RegexBuilder::new().exact_repetitions(RegexBuilder::new().dot(), 8)), Count::Overflow);
+    /// ```
+    pub fn count(&self) -> Count {
+        self.count
+    }
+
+    /// Returns an iterator over the strings of the language in shortlex order.
+    ///
+    /// The iterator never ends if the language is infinite.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let language = RegexBuilder::new()
+    ///     .zero_or_more(RegexBuilder::new().character_class("ab"))
+    ///     .language()
+    ///     .unwrap();
+    /// let strings: Vec<String> = language.strings().take(8).collect();
+    /// assert_eq!(strings, ["", "a", "b", "aa", "ab", "ba", "bb", "aaa"]);
+    /// ```
+    pub fn strings(&self) -> Strings<'_> {
+        let next_length = match self.count {
+            Count::Finite(0) => None,
+            _ => Some(0),
+        };
+        Strings {
+            language: self,
+            reach: vec![self.states.iter().map(|state| state.accepting).collect()],
+            next_length,
+            length: 0,
+            stack: Vec::new(),
+            text: Vec::new(),
+        }
+    }
+}
+
+/// Iterator over the strings of a [`Language`] in shortlex order, returned by
+/// [`Language::strings`].
+#[derive(Clone, Debug)]
+pub struct Strings<'a> {
+    language: &'a Language,
+    /// For each length, whether each state can reach an accepting state in
+    /// exactly that many steps.
+    reach: Vec<Vec<bool>>,
+    next_length: Option<usize>,
+    length: usize,
+    stack: Vec<Cursor>,
+    text: Vec<char>,
+}
+
+/// The next character to try from a state.
+#[derive(Clone, Debug)]
+struct Cursor {
+    state: usize,
+    transition: usize,
+    next: u32,
+}
+
+impl Strings<'_> {
+    /// Returns `true` if `state` can reach an accepting state in exactly `steps` steps.
+    fn can_finish(&mut self, state: usize, steps: usize) -> bool {
+        while self.reach.len() <= steps {
+            let last = self.reach.last().unwrap();
+            let next = self
+                .language
+                .states
+                .iter()
+                .map(|state| state.transitions.iter().any(|&(_, _, target)| last[target]))
+                .collect();
+            self.reach.push(next);
+        }
+        self.reach[steps][state]
+    }
+
+    /// Moves the cursor on top of the stack to its next character that leads to a
+    /// state that can finish in `steps` steps, returning the character and that state.
+    fn advance(&mut self, steps: usize) -> Option<(char, usize)> {
+        let cursor = self.stack.last().unwrap().clone();
+        let transitions = &self.language.states[cursor.state].transitions;
+        let (mut transition, mut next) = (cursor.transition, cursor.next);
+        while let Some(&(start, end, target)) = transitions.get(transition) {
+            next = next.max(start as u32);
+            if next <= end as u32 && self.can_finish(target, steps) {
+                // Skip the surrogates, which are not characters.
+                if let Some(c) = (next..=end as u32).find_map(char::from_u32) {
+                    let top = self.stack.last_mut().unwrap();
+                    top.transition = transition;
+                    top.next = c as u32 + 1;
+                    return Some((c, target));
+                }
+            }
+            transition += 1;
+            next = 0;
+        }
+        None
+    }
+}
+
+impl Iterator for Strings<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            if self.stack.is_empty() {
+                // Starts on the strings of the next length.
+                let length = self.next_length?;
+                self.next_length = match self.language.longest {
+                    Some(longest) if length >= longest => None,
+                    _ => Some(length + 1),
+                };
+                self.length = length;
+                if self.can_finish(0, length) {
+                    if length == 0 {
+                        return Some(String::new());
+                    }
+                    self.stack.push(Cursor {
+                        state: 0,
+                        transition: 0,
+                        next: 0,
+                    });
+                }
+                continue;
+            }
+            let depth = self.stack.len() - 1;
+            let steps = self.length - depth - 1;
+            match self.advance(steps) {
+                Some((c, target)) => {
+                    self.text.truncate(depth);
+                    self.text.push(c);
+                    if steps == 0 {
+                        return Some(self.text.iter().collect());
+                    }
+                    self.stack.push(Cursor {
+                        state: target,
+                        transition: 0,
+                        next: 0,
+                    });
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+impl Backend for Language {
+    type Error = Box<regex_syntax::Error>;
+
+    fn name() -> &'static str {
+        "simple_regex::language::Language"
+    }
+
+    fn unsupported(node: &Node) -> Vec<Unsupported<'_>> {
+        fn visit<'a>(node: &'a Node, multi_line: bool, unsupported: &mut Vec<Unsupported<'a>>) {
+            let construct = match node {
+                Node::Raw(_) => "raw pattern text",
+                Node::Look { .. } => "look-around",
+                Node::Backreference(_) | Node::NamedBackreference(_) => "backreferences",
+                Node::Anchor(Anchor::WordBoundary | Anchor::NonWordBoundary) => "word boundaries",
+                Node::Anchor(Anchor::StartOfLine | Anchor::EndOfLine) if multi_line => "multi-line anchors",
+                Node::Flags { flags, node } => {
+                    let multi_line = if flags.enabled.contains(&Flag::MultiLine) {
+                        true
+                    } else {
+                        multi_line && !flags.disabled.contains(&Flag::MultiLine)
+                    };
+                    return visit(node, multi_line, unsupported);
+                }
+                Node::Repeat { node, .. } | Node::Group { node, .. } => return visit(node, multi_line, unsupported),
+                Node::Alternation(nodes) | Node::Concat(nodes) => {
+                    return nodes.iter().for_each(|node| visit(node, multi_line, unsupported));
+                }
+                _ => return,
+            };
+            unsupported.push(Unsupported { node, construct });
+        }
+        let mut unsupported = Vec::new();
+        visit(node, false, &mut unsupported);
+        unsupported
+    }
+
+    fn compile(nodes: &[Node], _: &str) -> Result<Self, Self::Error> {
+        let mut nfa = Nfa { states: Vec::new() };
+        let mut start = Nfa::MATCH;
+        for node in nodes.iter().rev() {
+            start = nfa.compile(node, Context::default(), start)?;
+        }
+        let states = nfa.determinize(start);
+        let (count, longest) = count(&states);
+        Ok(Language { states, count, longest })
+    }
+}
+
+/// A nondeterministic automaton, built backwards from its accepting state.
+struct Nfa {
+    states: Vec<NfaState>,
+}
+
+enum NfaState {
+    Class(Box<[(char, char)]>, usize),
+    Split(Vec<usize>),
+    StartText(usize),
+    EndText(usize),
+}
+
+impl Nfa {
+    const MATCH: usize = usize::MAX;
+
+    fn push(&mut self, state: NfaState) -> usize {
+        self.states.push(state);
+        self.states.len() - 1
+    }
+
+    /// Returns a state matching `node` and continuing at `next`.
+    fn compile(&mut self, node: &Node, context: Context, next: usize) -> Result<usize, Box<regex_syntax::Error>> {
+        Ok(match node {
+            Node::Empty | Node::Raw(_) | Node::Look { .. } => next,
+            Node::Literal(c) => self.class(&Node::Literal(*c).to_string(), context, next)?,
+            Node::Text(text) => {
+                let mut next = next;
+                for c in text.chars().rev() {
+                    next = self.class(&Node::Literal(c).to_string(), context, next)?;
+                }
+                next
+            }
+            Node::Dot | Node::Class(_) => self.class(&node.to_string(), context, next)?,
+            Node::Anchor(Anchor::StartOfLine | Anchor::StartOfText) => self.push(NfaState::StartText(next)),
+            Node::Anchor(Anchor::EndOfLine | Anchor::EndOfText) => self.push(NfaState::EndText(next)),
+            Node::Anchor(_) => next,
+            Node::Repeat { node, repetition, .. } => {
+                let (min, max) = match *repetition {
+                    Repetition::ZeroOrMore => (0, None),
+                    Repetition::OneOrMore => (1, None),
+                    Repetition::ZeroOrOne => (0, Some(1)),
+                    Repetition::Exactly(n) => (n, Some(n)),
+                    Repetition::AtLeast(n) => (n, None),
+                    Repetition::Between(n, m) if m < n => return Err(backtrack::reversed_range(*repetition)),
+                    Repetition::Between(n, m) => (n, Some(m)),
+                };
+                let mut next = next;
+                match max {
+                    None => {
+                        let split = self.push(NfaState::Split(Vec::new()));
+                        let body = self.compile(node, context, split)?;
+                        self.states[split] = NfaState::Split(vec![body, next]);
+                        next = split;
+                    }
+                    Some(max) => {
+                        for _ in min..max {
+                            let body = self.compile(node, context, next)?;
+                            next = self.push(NfaState::Split(vec![body, next]));
+                        }
+                    }
+                }
+                for _ in 0..min {
+                    next = self.compile(node, context, next)?;
+                }
+                next
+            }
+            Node::Group { node, .. } => self.compile(node, context, next)?,
+            Node::Flags { flags, node } => {
+                let mut context = context;
+                flags.enabled.iter().for_each(|flag| context.set(*flag, true));
+                flags.disabled.iter().for_each(|flag| context.set(*flag, false));
+                self.compile(node, context, next)?
+            }
+            Node::Backreference(_) | Node::NamedBackreference(_) => next,
+            Node::Alternation(branches) => {
+                let branches = branches
+                    .iter()
+                    .map(|branch| self.compile(branch, context, next))
+                    .collect::<Result<_, _>>()?;
+                self.push(NfaState::Split(branches))
+            }
+            Node::Concat(nodes) => {
+                let mut next = next;
+                for node in nodes.iter().rev() {
+                    next = self.compile(node, context, next)?;
+                }
+                next
+            }
+        })
+    }
+
+    fn class(&mut self, syntax: &str, context: Context, next: usize) -> Result<usize, Box<regex_syntax::Error>> {
+        let ranges = backtrack::class_ranges(&context.scope(syntax))?;
+        Ok(self.push(NfaState::Class(ranges, next)))
+    }
+
+    /// Returns the states reachable from `states` without consuming a character,
+    /// as `(state, ended)` pairs, where `ended` marks states reached through an
+    /// end-of-text anchor that can no longer consume characters.
+    fn closure(&self, states: impl IntoIterator<Item = usize>, at_start: bool) -> Vec<(usize, bool)> {
+        let mut seen: Vec<(usize, bool)> = Vec::new();
+        let mut stack: Vec<(usize, bool)> = states.into_iter().map(|state| (state, false)).collect();
+        while let Some((state, ended)) = stack.pop() {
+            if seen.contains(&(state, ended)) {
+                continue;
+            }
+            seen.push((state, ended));
+            match self.states.get(state) {
+                Some(NfaState::Split(targets)) => stack.extend(targets.iter().map(|&target| (target, ended))),
+                Some(NfaState::StartText(next)) if at_start => stack.push((*next, ended)),
+                Some(NfaState::EndText(next)) => stack.push((*next, true)),
+                _ => {}
+            }
+        }
+        seen.sort_unstable();
+        seen
+    }
+
+    /// Builds the deterministic automaton by subset construction. State 0 is the start.
+    fn determinize(&self, start: usize) -> Vec<State> {
+        let mut ids: HashMap<Vec<(usize, bool)>, usize> = HashMap::new();
+        let mut sets = vec![self.closure([start], true)];
+        ids.insert(sets[0].clone(), 0);
+        let mut states = Vec::new();
+        while let Some(set) = sets.get(states.len()).cloned() {
+            let accepting = set.iter().any(|&(state, _)| state == Nfa::MATCH);
+            let classes: Vec<(&[(char, char)], usize)> = set
+                .iter()
+                .filter_map(|&(state, ended)| match self.states.get(state) {
+                    Some(NfaState::Class(ranges, next)) if !ended => Some((&ranges[..], *next)),
+                    _ => None,
+                })
+                .collect();
+            // Split the characters at every range boundary, so that all characters
+            // between two boundaries lead to the same states.
+            let mut targets: BTreeMap<u32, Vec<usize>> = BTreeMap::new();
+            for (ranges, _) in &classes {
+                for &(start, end) in ranges.iter() {
+                    targets.entry(start as u32).or_default();
+                    targets.entry(end as u32 + 1).or_default();
+                }
+            }
+            let bounds: Vec<u32> = targets.keys().copied().collect();
+            for (ranges, next) in &classes {
+                for &(start, end) in ranges.iter() {
+                    let first = bounds.binary_search(&(start as u32)).unwrap();
+                    for bound in &bounds[first..] {
+                        if *bound > end as u32 {
+                            break;
+                        }
+                        targets.get_mut(bound).unwrap().push(*next);
+                    }
+                }
+            }
+            let mut transitions: Vec<(char, char, usize)> = Vec::new();
+            for (i, (&start, nexts)) in targets.iter().enumerate() {
+                if nexts.is_empty() {
+                    continue;
+                }
+                let end = bounds[i + 1] - 1;
+                let set = self.closure(nexts.iter().copied(), false);
+                let next = ids.len();
+                let target = *ids.entry(set.clone()).or_insert_with(|| {
+                    sets.push(set);
+                    next
+                });
+                let (start, end) = (char_at_or_after(start), char_at_or_before(end));
+                if start > end {
+                    continue;
+                }
+                match transitions.last_mut() {
+                    Some(last) if last.2 == target && last.1 as u32 + 1 >= start as u32 => last.1 = end,
+                    _ => transitions.push((start, end, target)),
+                }
+            }
+            states.push(State { accepting, transitions });
+        }
+        states
+    }
+}
+
+/// Range boundaries always fall on characters, except next to the surrogates.
+fn char_at_or_after(code: u32) -> char {
+    char::from_u32(code).unwrap_or('\u{E000}')
+}
+
+fn char_at_or_before(code: u32) -> char {
+    char::from_u32(code).unwrap_or('\u{D7FF}')
+}
+
+/// Returns the number of characters in a range, which never includes surrogates.
+fn len(start: char, end: char) -> u128 {
+    let surrogates = if start < '\u{E000}' && end > '\u{D7FF}' { 0x800 } else { 0 };
+    (end as u32 - start as u32 + 1 - surrogates) as u128
+}
+
+/// Counts the strings accepted from the start state, along with the length of the
+/// longest one when there are finitely many.
+fn count(states: &[State]) -> (Count, Option<usize>) {
+    // Only states that can reach an accepting state matter.
+    let mut live: Vec<bool> = states.iter().map(|state| state.accepting).collect();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (i, state) in states.iter().enumerate() {
+            if !live[i] && state.transitions.iter().any(|&(_, _, target)| live[target]) {
+                live[i] = true;
+                changed = true;
+            }
+        }
+    }
+    if !live[0] {
+        return (Count::Finite(0), Some(0));
+    }
+    // Visits the live states reachable from the start in reverse topological order,
+    // finding a cycle if there is one.
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        New,
+        Open,
+        Done,
+    }
+    let mut marks = vec![Mark::New; states.len()];
+    let mut order = Vec::new();
+    let mut stack = vec![(0, 0)];
+    marks[0] = Mark::Open;
+    while let Some((state, transition)) = stack.pop() {
+        match states[state].transitions.get(transition) {
+            Some(&(_, _, target)) => {
+                stack.push((state, transition + 1));
+                if !live[target] {
+                    continue;
+                }
+                match marks[target] {
+                    Mark::Open => return (Count::Infinite, None),
+                    Mark::New => {
+                        marks[target] = Mark::Open;
+                        stack.push((target, 0));
+                    }
+                    Mark::Done => {}
+                }
+            }
+            None => {
+                marks[state] = Mark::Done;
+                order.push(state);
+            }
+        }
+    }
+    let mut counts: Vec<Option<u128>> = vec![Some(0); states.len()];
+    let mut longest = vec![0; states.len()];
+    for &state in &order {
+        let mut count = Some(states[state].accepting as u128);
+        for &(start, end, target) in &states[state].transitions {
+            if live[target] {
+                let paths = counts[target].and_then(|paths| paths.checked_mul(len(start, end)));
+                count = count.zip(paths).and_then(|(count, paths)| count.checked_add(paths));
+                longest[state] = longest[state].max(longest[target] + 1);
+            }
+        }
+        counts[state] = count;
+    }
+    let count = counts[0].map_or(Count::Overflow, Count::Finite);
+    (count, Some(longest[0]))
+}
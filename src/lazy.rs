@@ -0,0 +1,87 @@
+//! Patterns stored in a `static` and compiled on first use.
+
+use std::fmt;
+use std::ops::Deref;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::{BuildError, RegexBuilder};
+
+/// A pattern defined by a builder function, compiled once, thread-safely, the first
+/// time it is used.
+///
+/// `LazyPattern::new` is a `const fn`, so the pattern can live in a `static` and be
+/// shared by every caller instead of being recompiled on each use. The compiled
+/// [`Regex`] is available through [`LazyPattern::regex`] or, thanks to `Deref`, by
+/// calling `Regex` methods on the pattern directly.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{LazyPattern, RegexBuilder};
+///
+/// static ZIP_CODE: LazyPattern = LazyPattern::new(|| {
+///     RegexBuilder::new()
+///         .start_of_line()
+///         .exact_repetitions(RegexBuilder::new().digit(), 5)
+///         .end_of_line()
+/// });
+///
+/// assert!(ZIP_CODE.is_match("90210"));
+/// assert!(!ZIP_CODE.is_match("9021"));
+/// assert_eq!(ZIP_CODE.as_str(), "^\\d{5}$");
+/// ```
+pub struct LazyPattern {
+    build: fn() -> RegexBuilder,
+    regex: OnceLock<Result<Regex, BuildError>>,
+}
+
+impl LazyPattern {
+    /// Creates a pattern that is built by `build` and compiled on first use.
+    pub const fn new(build: fn() -> RegexBuilder) -> Self {
+        LazyPattern {
+            build,
+            regex: OnceLock::new(),
+        }
+    }
+
+    /// Returns a new builder for the pattern.
+    pub fn builder(&self) -> RegexBuilder {
+        (self.build)()
+    }
+
+    /// Returns the compiled pattern, compiling it if this is the first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`BuildError`] of the first compilation if the pattern does not
+    /// compile. The pattern is not compiled again.
+    pub fn try_regex(&self) -> Result<&Regex, &BuildError> {
+        self.regex.get_or_init(|| self.builder().to_regex()).as_ref()
+    }
+
+    /// Returns the compiled pattern, compiling it if this is the first use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pattern does not compile.
+    pub fn regex(&self) -> &Regex {
+        self.try_regex().unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl Deref for LazyPattern {
+    type Target = Regex;
+
+    /// Returns the compiled pattern like [`LazyPattern::regex`].
+    fn deref(&self) -> &Regex {
+        self.regex()
+    }
+}
+
+impl fmt::Debug for LazyPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyPattern").field("regex", &self.regex.get()).finish_non_exhaustive()
+    }
+}
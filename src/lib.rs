@@ -4,24 +4,51 @@
 //!
 //!## Features
 //!
-//!- **Expressive Builder Pattern:** Easily construct regular expressions using a chainable builder pattern.
+//!- **Expressive Builder Pattern:** Easily construct regular expressions using a chainable builder pattern. Methods take the builder by value, so long chains and deeply nested builders are built in linear time.
 //!- **Concise API:** The API is designed to be concise and intuitive, making it easy to build complex regex patterns.
-//!- **Modifier Support:** Add modifiers like case-insensitive, global search, multiline, and dot-all to your regex patterns.
-//!- **Quantifiers:** Use quantifiers like zero or more, one or more, zero or one, exact repetitions, minimum repetitions, and range repetitions.
-//!- **Ansi Formatting:** Includes an ANSI formatting module for adding color to terminal output.
+//!- **Modifier Support:** Scope inline flags like case-insensitive, multiline, dot-all, ignore-whitespace, swap-greed and CRLF to parts of your regex patterns, alone or combined.
+//!- **Expression Tree:** Patterns are kept as a typed expression tree (`simple_regex::ast`) and only rendered to text when built.
+//!- **Pattern Parsing:** `RegexBuilder::parse` turns an existing pattern string back into a builder, so legacy patterns can be edited through the builder API, and `to_rust_code` (or the `simple-regex to-rust` command) prints the equivalent chain of builder calls.
+//!- **Explanations:** `explain` describes a pattern in plain English, naming the builder method behind each piece.
+//!- **Quantifiers:** Use quantifiers like zero or more, one or more, zero or one, exact repetitions, minimum repetitions, and range repetitions. Multi-atom patterns are grouped automatically, so a quantifier always applies to the whole sub-builder.
+//!- **Helpful Errors:** `to_regex` returns a `BuildError` that underlines the part of the generated pattern that failed to compile and names the builder method that produced it.
+//!- **Pluggable Backends:** Compile the same builder into `regex::Regex`, `regex::bytes::Regex` or `regex::RegexSet` through the `Backend` trait, which reports unsupported constructs before compiling.
+//!- **Look-around and Backreferences:** `to_matcher` falls back to a bundled backtracking engine, bounded by a step limit, when a pattern uses constructs the `regex` crate does not support.
+//!- **Named Groups:** `named_group` and `named_group_handle` give a `Group` handle for backreferences and for reading captures by name, so reordering groups cannot break extraction code.
+//!- **Typed Captures:** `FromCaptures` converts named groups into struct fields through `FromStr`, with `Option` fields for optional groups and a `CaptureError` naming the group that failed. The `derive` feature adds `#[derive(FromCaptures)]`.
+//!- **Pattern Macros:** `pattern![start, ws*, any_of![...], end]` expands a compact DSL into builder calls, reporting unknown items at compile time, and `regex![...]` compiles the pattern once into a cached `static`.
+//!- **Lazy Patterns:** `LazyPattern::new` is a `const fn`, so a pattern can live in a `static` and be compiled once, thread-safely, on first use.
+//!- **Common Formats:** The `patterns` module has builders for email addresses, URLs, IPv4, IPv6, CIDR, MAC addresses, UUIDs, ISO 8601 dates and times, semantic versions, hex colors, payment card numbers (with a `luhn` check) and E.164 phone numbers, ready to embed with `then`.
+//!- **Phone and Postal Codes:** `patterns::phone` and `patterns::postal` have national formats for several countries, each tested against valid and invalid samples, and `for_country` selects one by ISO country code at runtime.
+//!- **Random Strings:** With the `rand` feature, `generate` produces random strings that match a pattern, respecting classes, alternations, anchors and backreferences, with a configurable cap on unbounded repetitions. Useful for fuzzing parsers.
+//!- **Languages:** `language` compiles a pattern into the set of strings it matches as a whole, which reports their exact number (or that there are infinitely many) and lists them in shortlex order, to check that a validator accepts exactly the intended strings.
+//!- **Static Analysis:** `analyze` reports the minimum and maximum match length, whether the empty string matches, whether matches are anchored at both ends, and the characters a match can start with, to pre-filter inputs and catch validators that forgot `start_of_line` or `end_of_line`.
+//!- **Ansi Formatting:** Includes an ANSI formatting module with composable styles, 256-color and truecolor support, and `NO_COLOR`/terminal detection. `to_colored_string` uses it to syntax-highlight built patterns.
 //!
 //!## ANSI Module
 //!
 //!### Colors
 //!
-//!- `fg_black(text: String) -> String`: Formats the given text with black foreground color.
-//!- `fg_red(text: String) -> String`: Formats the given text with red foreground color.
-//!- `fg_green(text: String) -> String`: Formats the given text with green foreground color.
-//!- `fg_yellow(text: String) -> String`: Formats the given text with yellow foreground color.
-//!- `fg_blue(text: String) -> String`: Formats the given text with blue foreground color.
-//!- `fg_purple(text: String) -> String`: Formats the given text with purple foreground color.
-//!- `fg_cyan(text: String) -> String`: Formats the given text with cyan foreground color.
-//!- `fg_white(text: String) -> String`: Formats the given text with white foreground color.
+//!- `fg_black(text: impl Display) -> String`: Formats the given text with black foreground color, following the current `ColorChoice`.
+//!- `fg_red(text: impl Display) -> String`: Formats the given text with red foreground color, following the current `ColorChoice`.
+//!- `fg_green(text: impl Display) -> String`: Formats the given text with green foreground color, following the current `ColorChoice`.
+//!- `fg_yellow(text: impl Display) -> String`: Formats the given text with yellow foreground color, following the current `ColorChoice`.
+//!- `fg_blue(text: impl Display) -> String`: Formats the given text with blue foreground color, following the current `ColorChoice`.
+//!- `fg_purple(text: impl Display) -> String`: Formats the given text with purple foreground color, following the current `ColorChoice`.
+//!- `fg_cyan(text: impl Display) -> String`: Formats the given text with cyan foreground color, following the current `ColorChoice`.
+//!- `fg_white(text: impl Display) -> String`: Formats the given text with white foreground color, following the current `ColorChoice`.
+//!
+//!### Styles
+//!
+//!- `Style::new().fg(Color).bg(Color).bold()`: Combines a foreground color, a background color and attributes (`bold`, `dim`, `italic`, `underline`, `blink`, `reverse`, `hidden`, `strikethrough`).
+//!- `Color`: The 8 standard colors, their `Bright*` variants, `Fixed(u8)` from the 256-color palette and `Rgb(u8, u8, u8)`.
+//!- `Style::paint(value) -> Painted`: Wraps a value that displays in the style, without allocating.
+//!- `set_color_choice(ColorChoice)`: Chooses between `Auto`, `Always` and `Never`. `Auto` leaves out escape codes when `NO_COLOR` is set or standard output is not a terminal.
+//!
+//!### Matches
+//!
+//!- `highlight_matches(regex: &Regex, haystack: &str) -> String`: Colors every match of the regex in the text, with a color per capture group.
+//!- `match_legend(regex: &Regex) -> String`: Lists the capture groups, by name or index, in the colors used by `highlight_matches`.
 //!
 //!## RegexBuilder Struct
 //!
@@ -32,7 +59,12 @@
 //!| Function                                                             | Description                                                                                  | Example                                                                                            | Result                                     |
 //!| -------------------------------------------------------------------- | -------------------------------------------------------------------------------------------- | -------------------------------------------------------------------------------------------------- | ------------------------------------------ |
 //!| `new() -> Self`                                                      | Creates a new instance of `RegexBuilder`.                                                    | `RegexBuilder::new()`                                                                              | `RegexBuilder` instance                    |
-//!| `literal(char) -> Self`                                              | Appends a literal character to the regex.                                                    | `.literal('a')`                                                                                    | "a"                                        |
+//!| `nodes() -> &[Node]`                                                 | Returns the expression tree nodes appended so far.                                           | `.nodes()`                                                                                         | `&[Node]`                                  |
+//!| `into_node() -> Node`                                                | Converts the builder into a single expression tree node.                                     | `.into_node()`                                                                                     | `Node`                                     |
+//!| `parse(pattern: &str) -> Result<Self, ParseError>`                   | Parses an existing pattern into a builder whose `build()` is an equivalent pattern.          | `RegexBuilder::parse("[a-z]+?")`                                                                   | "\[a-z\]+?"                                |
+//!| `literal(char) -> Self`                                              | Appends a literal character to the regex, escaped only when needed.                          | `.literal('a')`                                                                                    | "a"                                        |
+//!| `string(string: &str) -> Self`                                       | Appends a string to the regex, escaping regex metacharacters.                                | `.string("1.5")`                                                                                   | "1\\.5"                                    |
+//!| `raw(pattern: &str) -> Self`                                         | Appends pattern text to the regex as-is, without escaping.                                   | `.raw("a.c")`                                                                                      | "a.c"                                      |
 //!| `dot() -> Self`                                                      | Appends a dot (.) to the regex, matching any single character.                               | `.dot()`                                                                                           | "."                                        |
 //!| `escape(char) -> Self`                                               | Appends an escaped character to the regex.                                                   | `.escape('[')`                                                                                     | "\\["                                      |
 //!| `start_of_line() -> Self`                                            | Appends the start of line anchor (^) to the regex.                                           | `.start_of_line()`                                                                                 | "^"                                        |
@@ -52,18 +84,38 @@
 //!| `exact_repetitions(regex: RegexBuilder, n: usize) -> Self`           | Appends an exact repetitions quantifier to the regex.                                        | `.exact_repetitions(RegexBuilder::new().digit(), 3)`                                               | "\\d{3}"                                   |
 //!| `min_repetitions(regex: RegexBuilder, n: usize) -> Self`             | Appends a minimum repetitions quantifier to the regex.                                       | `.min_repetitions(RegexBuilder::new().digit(), 3)`                                                 | "\\d{3,}"                                  |
 //!| `range_repetitions(regex: RegexBuilder, n: usize, m: usize) -> Self` | Appends a range repetitions quantifier to the regex.                                         | `.range_repetitions(RegexBuilder::new().digit(), 3, 5)`                                            | "\\d{3,5}"                                 |
+//!| `lazy(regex: RegexBuilder) -> Self`                                  | Appends the regex with its quantifiers made lazy, matching as few repetitions as possible.   | `.lazy(RegexBuilder::new().one_or_more(RegexBuilder::new().dot()))`                                | ".+?"                                      |
+//!| `then(regex: RegexBuilder) -> Self`                                  | Appends the patterns of another builder, without grouping them.                              | `.then(RegexBuilder::new().digit())`                                                               | "\\d"                                      |
 //!| `group(regex: RegexBuilder) -> Self`                                 | Appends a group to the regex.                                                                | `.group(RegexBuilder::new().character_class("ab"))`                                                | "(?:\[ab\])"                               |
 //!| `backreference(group_number: usize) -> Self`                         | Appends a backreference to a capturing group in the regex.                                   | `.backreference(1)`                                                                                | "\\1"                                      |
+//!| `backreference_to(group: &Group) -> Self`                            | Appends a backreference to a named capturing group in the regex.                             | `.backreference_to(&year)`                                                                         | "\\k\<year>"                               |
 //!| `word_boundary() -> Self`                                            | Appends a word boundary anchor (\b) to the regex.                                            | `.word_boundary()`                                                                                 | "\\b"                                      |
 //!| `non_word_boundary() -> Self`                                        | Appends a non-word boundary anchor (\B) to the regex.                                        | `.non_word_boundary()`                                                                             | "\\B"                                      |
-//!| `case_insensitive(regex: RegexBuilder) -> Self`                      | Appends a case-insensitive modifier to the regex.                                            | `.case_insensitive(RegexBuilder::new().character_class("a"))`                                      | "(?i\[a\])"                                |
-//!| `global_search(regex: RegexBuilder) -> Self`                         | Appends a global search modifier to the regex.                                               | `.global_search(RegexBuilder::new().character_class("a"))`                                         | "(?g\[a\])"                                |
-//!| `multiline(regex: RegexBuilder) -> Self`                             | Appends a multiline modifier to the regex.                                                   | `.multiline(RegexBuilder::new().character_class("a"))`                                             | "(?m\[a\])"                                |
-//!| `dot_all(regex: RegexBuilder) -> Self`                               | Appends a dot-all modifier to the regex, allowing '.' to match newline characters.           | `.dot_all(RegexBuilder::new().character_class("a"))`                                               | "(?s\[a\])"                                |
+//!| `case_insensitive(regex: RegexBuilder) -> Self`                      | Appends a case-insensitive modifier to the regex.                                            | `.case_insensitive(RegexBuilder::new().character_class("a"))`                                      | "(?i:\[a\])"                               |
+//!| `global_search(regex: RegexBuilder) -> Self`                         | Deprecated: appends the regex unchanged; use `find_iter`.                                    | `.global_search(RegexBuilder::new().character_class("a"))`                                         | "\[a\]"                                    |
+//!| `multiline(regex: RegexBuilder) -> Self`                             | Appends a multiline modifier to the regex.                                                   | `.multiline(RegexBuilder::new().character_class("a"))`                                             | "(?m:\[a\])"                               |
+//!| `dot_all(regex: RegexBuilder) -> Self`                               | Appends a dot-all modifier to the regex, allowing '.' to match newline characters.           | `.dot_all(RegexBuilder::new().character_class("a"))`                                               | "(?s:\[a\])"                               |
+//!| `ignore_whitespace(regex: RegexBuilder) -> Self`                     | Appends an ignore-whitespace modifier to the regex.                                          | `.ignore_whitespace(RegexBuilder::new().character_class("a"))`                                     | "(?x:\[a\])"                               |
+//!| `swap_greed(regex: RegexBuilder) -> Self`                            | Appends a swap-greed modifier to the regex, making quantifiers lazy by default.              | `.swap_greed(RegexBuilder::new().character_class("a"))`                                            | "(?U:\[a\])"                               |
+//!| `crlf(regex: RegexBuilder) -> Self`                                  | Appends a CRLF modifier to the regex, treating `\r\n` as a line terminator.                  | `.crlf(RegexBuilder::new().character_class("a"))`                                                  | "(?R:\[a\])"                               |
+//!| `with_flags(flags: impl IntoIterator<Item = Flag>, regex: RegexBuilder) -> Self` | Appends the regex with several inline flags enabled at once.                                 | `.with_flags([Flag::CaseInsensitive, Flag::MultiLine], RegexBuilder::new().character_class("a"))`  | "(?im:\[a\])"                              |
 //!| `alternative(regex1: RegexBuilder, regex2: RegexBuilder) -> Self`    | Appends an alternative (\|) to the regex, allowing either of the provided patterns to match. | `.alternative(RegexBuilder::new().character_class("a"), RegexBuilder::new().character_class("b"))` | "\[a\]\|\[b\]"                             |
+//!| `any_of(regexes: impl IntoIterator<Item = RegexBuilder>) -> Self`    | Appends an alternation of any number of patterns, grouped when needed.                       | `.any_of([RegexBuilder::new().string("a"), RegexBuilder::new().digit()])`                          | "a\|\\d"                                   |
 //!| `capturing_group(regex: RegexBuilder) -> Self`                       | Appends a capturing group to the regex.                                                      | `.capturing_group(RegexBuilder::new().character_class("a"))`                                       | "(\[a\])"                                  |
-//!| `to_regex()`                                                         | Converts the current `RegexBuilder` into a `Regex` object.                                   |                                                                                                    | "Returns a `Result<Regex, regex::Error>`." |
+//!| `named_group(name: &str, regex: RegexBuilder) -> (Self, Group)`      | Appends a named capturing group to the regex and returns a `Group` handle to it.             | `.named_group("year", RegexBuilder::new().digit())`                                                | "(?P\<year>\\d)" and a `Group`             |
+//!| `named_group_handle(name: &str) -> Option<Group>`                    | Returns a `Group` handle to a named group, for backreferences and `Captures` access.         | `.named_group_handle("year")`                                                                      | "Returns an `Option<Group>`."              |
+//!| `to_regex()`                                                         | Converts the current `RegexBuilder` into a `Regex` object.                                   |                                                                                                    | "Returns a `Result<Regex, BuildError>`."  |
 //!| `to_regex_or_panic()`                                                | Converts the current `RegexBuilder` into a `Regex` object or panics if an error occurs.      |                                                                                                    | "Returns a `Regex` object."                |
+//!| `to_colored_string()`                                                | Builds the regex with ANSI colors for anchors, classes, quantifiers, escapes and groups.     | `.to_colored_string()`                                                                             | "Returns a `String`."                      |
+//!| `compile::<B: Backend>()`                                            | Compiles the regex with the given backend, e.g. `regex::bytes::Regex` or `regex::RegexSet`.  | `.compile::<regex::bytes::Regex>()`                                                                | "Returns a `Result<B, BuildError>`."       |
+//!| `unsupported::<B: Backend>()`                                        | Lists the constructs in the regex that the given backend cannot compile.                     | `.unsupported::<regex::Regex>()`                                                                   | "Returns a `Vec<Unsupported>`."            |
+//!| `to_matcher()`                                                       | Compiles the regex with `regex::Regex`, or the backtracking engine for look-around and backreferences. | `.to_matcher()`                                                                                    | "Returns a `Result<Matcher, BuildError>`." |
+//!| `to_rust_code()`                                                     | Returns the builder method calls that rebuild the regex, formatted as Rust code.             | `RegexBuilder::parse("a+")?.to_rust_code()`                                                        | "Returns a `String`."                      |
+//!| `explain()`                                                          | Describes the regex in plain English, naming the builder method behind each piece.           | `.explain()`                                                                                       | "Returns a `String`."                      |
+//!| `language()`                                                         | Compiles the regex into the set of strings it matches, to count or list them.                | `.language()?.strings().take(10)`                                                                  | "Returns a `Result<Language, BuildError>`." |
+//!| `analyze()`                                                          | Returns the minimum and maximum match length, nullability, anchoring and first characters.   | `.analyze().min_len`                                                                               | "Returns an `Analysis`."                   |
+//!| `generate(rng: &mut R)`                                              | Returns a random string the whole regex matches, for fuzzing. Requires the `rand` feature.   | `.generate(&mut rand::thread_rng())`                                                               | "Returns an `Option<String>`."             |
+//!| `generator()`                                                        | Returns a generator of matching strings with configurable repetition caps.                   | `.generator().with_repeat_limit(3)`                                                                | "Returns a `Generator`."                   |
 //!
 //!### Examples
 //!
@@ -79,73 +131,168 @@
 
 use regex::Regex;
 
-pub mod ansi {
-    const ANSI_RESET: &str = "\x1b[0m";
-    const ANSI_BLACK: &str = "\x1b[30m";
-    const ANSI_RED: &str = "\x1b[31m";
-    const ANSI_GREEN: &str = "\x1b[32m";
-    const ANSI_YELLOW: &str = "\x1b[33m";
-    const ANSI_BLUE: &str = "\x1b[34m";
-    const ANSI_PURPLE: &str = "\x1b[35m";
-    const ANSI_CYAN: &str = "\x1b[36m";
-    const ANSI_WHITE: &str = "\x1b[37m";
-
-    /// Formats the given text with black foreground color.
-    pub fn fg_black(text: String) -> String {
-        format!("{}{}{}", ANSI_BLACK, text, ANSI_RESET)
-    }
+pub mod analysis;
+pub mod ansi;
+pub mod ast;
+pub mod backend;
+pub mod backtrack;
+mod codegen;
+mod error;
+mod explain;
+#[cfg(feature = "rand")]
+pub mod generate;
+mod group;
+mod highlight;
+pub mod language;
+mod lazy;
+mod macros;
+mod parse;
+pub mod patterns;
 
-    /// Formats the given text with red foreground color.
-    pub fn fg_red(text: String) -> String {
-        format!("{}{}{}", ANSI_RED, text, ANSI_RESET)
-    }
+pub use backend::Backend;
+pub use error::{BuildError, CaptureError, ParseError};
+pub use group::{parse_group, parse_optional_group, FromCaptures, Group};
+pub use lazy::LazyPattern;
+/// Derives [`FromCaptures`] for a struct with named fields.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{FromCaptures, RegexBuilder};
+///
+/// #[derive(Debug, FromCaptures)]
+/// struct Version {
+///     major: u32,
+///     minor: u32,
+///     #[group("pre")]
+///     prerelease: Option<String>,
+/// }
+///
+/// let number = || RegexBuilder::new().one_or_more(RegexBuilder::new().digit());
+/// let word = RegexBuilder::new().one_or_more(RegexBuilder::new().word_character());
+/// let (builder, _) = RegexBuilder::new().named_group("major", number());
+/// let (builder, _) = builder.literal('.').named_group("minor", number());
+/// let (prerelease, _) = RegexBuilder::new().literal('-').named_group("pre", word);
+/// let regex = builder.optional(prerelease).to_regex().unwrap();
+/// let version = Version::from_captures(&regex.captures("1.12-beta").unwrap()).unwrap();
+/// assert_eq!((version.major, version.minor), (1, 12));
+/// assert_eq!(version.prerelease.as_deref(), Some("beta"));
+///
+/// let error = Version::from_captures(&regex.captures("1.99999999999").unwrap()).unwrap_err();
+/// assert_eq!(error.group(), "minor");
+/// ```
+#[cfg(feature = "derive")]
+pub use simple_regex_derive::FromCaptures;
 
-    /// Formats the given text with green foreground color.
-    pub fn fg_green(text: String) -> String {
-        format!("{}{}{}", ANSI_GREEN, text, ANSI_RESET)
-    }
-
-    /// Formats the given text with yellow foreground color.
-    pub fn fg_yellow(text: String) -> String {
-        format!("{}{}{}", ANSI_YELLOW, text, ANSI_RESET)
-    }
+/// Items used by the code that `#[derive(FromCaptures)]` and the pattern macros generate.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::group::{parse_group, parse_optional_group};
+    pub use crate::macros::Literal;
+    pub use regex::Captures;
 
-    /// Formats the given text with blue foreground color.
-    pub fn fg_blue(text: String) -> String {
-        format!("{}{}{}", ANSI_BLUE, text, ANSI_RESET)
-    }
-
-    /// Formats the given text with purple foreground color.
-    pub fn fg_purple(text: String) -> String {
-        format!("{}{}{}", ANSI_PURPLE, text, ANSI_RESET)
-    }
-
-    /// Formats the given text with cyan foreground color.
-    pub fn fg_cyan(text: String) -> String {
-        format!("{}{}{}", ANSI_CYAN, text, ANSI_RESET)
-    }
-
-    /// Formats the given text with white foreground color.
-    pub fn fg_white(text: String) -> String {
-        format!("{}{}{}", ANSI_WHITE, text, ANSI_RESET)
+    /// Creates a handle to the group named `name`, for `same_as(name)` in the pattern macros.
+    pub fn group(name: &str) -> crate::Group {
+        crate::Group::new(name)
     }
 }
 
+use ast::{Anchor, Class, ClassItem, ClassSet, Flag, Flags, GroupKind, LookKind, Node, PerlClass, Repetition};
+use backend::Unsupported;
+
 /// Builder for constructing regular expressions.
-#[derive(Clone)]
+///
+/// Every method takes the builder by value and returns it, so a chain of calls moves
+/// a single builder along instead of copying it at each step.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[must_use = "builder methods return the updated builder"]
 pub struct RegexBuilder {
-    pub value: String
+    nodes: Vec<Node>
 }
 
 impl RegexBuilder {
     /// Creates a new instance of `RegexBuilder`.
     pub fn new() -> Self {
         Self {
-            value: String::new()
+            nodes: Vec::new()
         } 
     }
+
+    /// Parses a pattern written in regular expression syntax into a builder, so that
+    /// existing patterns can be edited through the builder API.
+    ///
+    /// Besides the syntax of the `regex` crate, look-around assertions and numbered
+    /// backreferences are recognized. Lazy quantifiers and named groups are kept in
+    /// the tree, and `(?flags)` is turned into a flag group around the rest of the
+    /// enclosing group. Constructs the tree has no node for, such as `\p{Greek}`, are
+    /// kept as [`raw`](RegexBuilder::raw) text. The built pattern is equivalent to the
+    /// parsed one, though not always spelled the same way.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let builder = RegexBuilder::parse(r"^(\d{3})\.(\d{4})$").unwrap();
+    /// assert_eq!(builder.build(), r"^(\d{3})\.(\d{4})$");
+    ///
+    /// // Edit the parsed pattern with builder methods.
+    /// let builder = builder.case_insensitive(RegexBuilder::new().string("x"));
+    /// assert_eq!(builder.build(), r"^(\d{3})\.(\d{4})$(?i:x)");
+    ///
+    /// let builder = RegexBuilder::parse(r"(?x) \w+? \#  # comment").unwrap();
+    /// assert_eq!(builder.build(), r"\w+?\#");
+    /// let builder = RegexBuilder::parse(r"(?i)a|(?P<digit>[[:digit:]])|\p{Greek}").unwrap();
+    /// assert_eq!(builder.build(), r"(?i:a)|(?i:(?P<digit>[[:digit:]]))|(?i:\p{Greek})");
+    /// ```
+    pub fn parse(pattern: &str) -> Result<Self, ParseError> {
+        let nodes = match parse::parse(pattern)? {
+            Node::Empty => Vec::new(),
+            Node::Concat(nodes) => nodes,
+            node => vec![node],
+        };
+        Ok(Self { nodes })
+    }
+
+    /// Returns the nodes appended so far, in order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    /// use simple_regex::ast::{Anchor, Node};
+    ///
+    /// let builder = RegexBuilder::new().start_of_line().dot();
+    /// assert_eq!(builder.nodes(), &[Node::Anchor(Anchor::StartOfLine), Node::Dot]);
+    /// ```
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Converts the builder into a single expression tree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    /// use simple_regex::ast::Node;
+    ///
+    /// assert_eq!(RegexBuilder::new().dot().into_node(), Node::Dot);
+    /// assert_eq!(RegexBuilder::new().into_node(), Node::Empty);
+    /// ```
+    pub fn into_node(self) -> Node {
+        Node::concat(self.nodes)
+    }
+
+    fn push(mut self, node: Node) -> Self {
+        self.nodes.push(node);
+        self
+    }
     /// Converts the current `RegexBuilder` into a `Regex` object.
     ///
+    /// If the built pattern does not compile, the returned [`BuildError`] points at the
+    /// builder method that produced the offending part of it.
+    ///
     /// # Example
     ///
     /// ```
@@ -165,13 +312,90 @@ impl RegexBuilder {
     ///         eprintln!("Error creating regex: {}", e);
     ///     }
     /// }
+    ///
+    /// // The compiled pattern is the built one.
+    /// let builder = RegexBuilder::new().any_of([RegexBuilder::new().literal('a'), RegexBuilder::new().literal('b')]);
+    /// assert_eq!(builder.to_regex().unwrap().as_str(), builder.build());
+    /// ```
+    pub fn to_regex(&self) -> Result<Regex, BuildError> {
+        self.compile()
+    }
+
+    /// Compiles the current `RegexBuilder` with the given matching [`Backend`].
+    ///
+    /// Constructs the backend does not support are reported as a [`BuildError`] before
+    /// the pattern is compiled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let builder = RegexBuilder::new().string("ERROR").one_or_more(RegexBuilder::new().dot());
+    /// let regex: regex::bytes::Regex = builder.compile().unwrap();
+    /// assert!(regex.is_match(b"\x00\xffERROR: disk full"));
+    /// ```
+    pub fn compile<B: Backend>(&self) -> Result<B, BuildError> {
+        error::compile(&self.nodes)
+    }
+
+    /// Lists the constructs in the current `RegexBuilder` that the given [`Backend`] cannot compile.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use regex::Regex;
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let builder = RegexBuilder::new()
+    ///     .capturing_group(RegexBuilder::new().digit())
+    ///     .backreference(1);
+    /// let unsupported = builder.unsupported::<Regex>();
+    /// assert_eq!(unsupported.len(), 1);
+    /// assert_eq!(unsupported[0].to_string(), "backreferences in `backreference(1)`");
+    /// ```
+    pub fn unsupported<B: Backend>(&self) -> Vec<Unsupported<'_>> {
+        self.nodes.iter().flat_map(B::unsupported).collect()
+    }
+
+    /// Compiles the current `RegexBuilder` with the `regex` crate, falling back to the
+    /// bundled backtracking engine when the pattern uses look-around or backreferences.
+    ///
+    /// # Example
+    ///
     /// ```
-    pub fn to_regex(&self) -> Result<Regex, regex::Error> {
-        Regex::new(&self.build())
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// // A price not preceded by a minus sign.
+    /// let matcher = RegexBuilder::new()
+    ///     .negative_lookbehind(RegexBuilder::new().literal('-'))
+    ///     .literal('$')
+    ///     .one_or_more(RegexBuilder::new().digit())
+    ///     .to_matcher()
+    ///     .unwrap();
+    /// assert_eq!(matcher.find("-$5 or $12").unwrap().as_str(), "$12");
+    ///
+    /// // Like the `regex` crate, the backtracking engine has a size limit.
+    /// let (builder, digit) = RegexBuilder::new().named_group("digit", RegexBuilder::new().digit());
+    /// let error = builder
+    ///     .exact_repetitions(RegexBuilder::new().backreference_to(&digit), 200_000_000)
+    ///     .to_matcher()
+    ///     .unwrap_err();
+    /// assert!(error.message().starts_with("Compiled regex exceeds size limit"));
+    /// ```
+    pub fn to_matcher(&self) -> Result<backtrack::Matcher, BuildError> {
+        if self.unsupported::<Regex>().is_empty() {
+            self.compile().map(backtrack::Matcher::Regex)
+        } else {
+            self.compile().map(backtrack::Matcher::Backtracking)
+        }
     }
 
     /// Converts the current `RegexBuilder` into a `Regex` object or panics if an error occurs.
     ///
+    /// The pattern is compiled again on every call; store it in a [`LazyPattern`] to
+    /// compile it once.
+    ///
     /// # Panics
     ///
     /// Panics if the regex construction fails.
@@ -189,11 +413,11 @@ impl RegexBuilder {
     /// assert!(regex.is_match("a"));
     /// ```
     pub fn to_regex_or_panic(&self) -> Regex {
-        self.to_regex().unwrap()
+        self.to_regex().unwrap_or_else(|error| panic!("{}", error))
     }
 
 
-    /// Appends a literal character to the regular expression.
+    /// Appends a literal character to the regular expression, escaped only if it has a special meaning.
     ///
     /// # Example
     ///
@@ -201,16 +425,19 @@ impl RegexBuilder {
     /// use simple_regex::RegexBuilder;
     ///
     /// let regex = RegexBuilder::new().literal('a').build();
-    /// assert_eq!(regex, "\\a");
+    /// assert_eq!(regex, "a");
+    ///
+    /// let regex = RegexBuilder::new().literal('+').literal('é').literal('\n').build();
+    /// assert_eq!(regex, "\\+é\\n");
     /// ```
-    pub fn literal(&mut self, char_: char) -> Self {
-        self.value.push('\\');
-        self.value.push(char_);
-        self.clone()
+    pub fn literal(self, char_: char) -> Self {
+        self.push(Node::Literal(char_))
     }
 
 
-    /// Appends a string to the regular expression.
+    /// Appends a string to the regular expression, matched literally.
+    ///
+    /// Regex metacharacters in the string are escaped, so it is safe to pass user input.
     ///
     /// # Example
     ///
@@ -219,10 +446,27 @@ impl RegexBuilder {
     ///
     /// let regex = RegexBuilder::new().string("word").build();
     /// assert_eq!(regex, "word");
+    ///
+    /// let regex = RegexBuilder::new().string("1.5").build();
+    /// assert_eq!(regex, "1\\.5");
     /// ```
-    pub fn string(&mut self, string: &str) -> Self {
-        self.value.push_str(string);
-        self.clone()
+    pub fn string(self, string: &str) -> Self {
+        self.push(Node::Text(string.to_string()))
+    }
+
+    /// Appends pattern text to the regular expression as-is, without escaping anything.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let regex = RegexBuilder::new().raw("a.c").build();
+    /// assert_eq!(regex, "a.c");
+    /// assert!(RegexBuilder::new().raw("a.c").to_regex_or_panic().is_match("abc"));
+    /// ```
+    pub fn raw(self, pattern: &str) -> Self {
+        self.push(Node::Raw(pattern.to_string()))
     }
     
     /// Appends a dot (.) to the regular expression, matching any single character.
@@ -235,13 +479,20 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new().dot().build();
     /// assert_eq!(regex, ".");
     /// ```
-    pub fn dot(&mut self) -> Self {
-        self.value.push('.');
-        self.clone()
+    pub fn dot(self) -> Self {
+        self.push(Node::Dot)
     }
     
     /// Appends an escaped character to the regular expression.
     ///
+    /// Escaping a metacharacter matches it literally; `d`, `D`, `w`, `W`, `s` and `S` append
+    /// the Perl character classes, `b`, `B`, `A` and `z` the anchors, and `n`, `r`, `t`, `f`,
+    /// `v` and `a` the corresponding control characters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `\` followed by the character is not a meaningful escape sequence.
+    ///
     /// # Example
     ///
     /// ```
@@ -249,11 +500,38 @@ impl RegexBuilder {
     ///
     /// let regex = RegexBuilder::new().escape('[').build();
     /// assert_eq!(regex, "\\[");
+    ///
+    /// let regex = RegexBuilder::new().escape('d').escape('t').build();
+    /// assert_eq!(regex, "\\d\\t");
     /// ```
-    pub fn escape(&mut self, char_: char) -> Self {
-        self.value.push('\\');
-        self.value.push(char_);
-        self.clone()
+    ///
+    /// ```should_panic
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// RegexBuilder::new().escape('q');
+    /// ```
+    pub fn escape(self, char_: char) -> Self {
+        let node = match char_ {
+            'd' => Node::Class(Class::Perl(PerlClass::Digit)),
+            'D' => Node::Class(Class::Perl(PerlClass::NonDigit)),
+            'w' => Node::Class(Class::Perl(PerlClass::Word)),
+            'W' => Node::Class(Class::Perl(PerlClass::NonWord)),
+            's' => Node::Class(Class::Perl(PerlClass::Whitespace)),
+            'S' => Node::Class(Class::Perl(PerlClass::NonWhitespace)),
+            'b' => Node::Anchor(Anchor::WordBoundary),
+            'B' => Node::Anchor(Anchor::NonWordBoundary),
+            'A' => Node::Anchor(Anchor::StartOfText),
+            'z' => Node::Anchor(Anchor::EndOfText),
+            'n' => Node::Literal('\n'),
+            'r' => Node::Literal('\r'),
+            't' => Node::Literal('\t'),
+            'f' => Node::Literal('\x0C'),
+            'v' => Node::Literal('\x0B'),
+            'a' => Node::Literal('\x07'),
+            c if ast::is_meta_character(c) => Node::Literal(c),
+            c => panic!("`\\{}` is not a valid escape sequence", c),
+        };
+        self.push(node)
     }
     
     /// Appends the start of line anchor (^) to the regular expression.
@@ -266,9 +544,8 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new().start_of_line().build();
     /// assert_eq!(regex, "^");
     /// ```
-    pub fn start_of_line(&mut self) -> Self {
-        self.value.push('^');
-        self.clone()
+    pub fn start_of_line(self) -> Self {
+        self.push(Node::Anchor(Anchor::StartOfLine))
     }
     
     /// Appends the end of line anchor ($) to the regular expression.
@@ -281,13 +558,15 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new().end_of_line().build();
     /// assert_eq!(regex, "$");
     /// ```
-    pub fn end_of_line(&mut self) -> Self {
-        self.value.push('$');
-        self.clone()
+    pub fn end_of_line(self) -> Self {
+        self.push(Node::Anchor(Anchor::EndOfLine))
     }
     
     /// Appends a character class to the regular expression.
     ///
+    /// A leading `^` is a literal member of the class, not a negation; use
+    /// [`RegexBuilder::negated_character_class`] to negate a class.
+    ///
     /// # Example
     ///
     /// ```
@@ -295,12 +574,12 @@ impl RegexBuilder {
     ///
     /// let regex = RegexBuilder::new().character_class("abc").build();
     /// assert_eq!(regex, "[abc]");
+    ///
+    /// let regex = RegexBuilder::new().character_class("^a").build();
+    /// assert_eq!(regex, r"[\^a]");
     /// ```
-    pub fn character_class(&mut self, chars: &str) -> Self {
-        self.value.push('[');
-        self.value.push_str(chars);
-        self.value.push(']');
-        self.clone()
+    pub fn character_class(self, chars: &str) -> Self {
+        self.push(Node::Class(Class::Set(ClassSet::parse(false, chars))))
     }
     
     /// Appends a negated character class to the regular expression.
@@ -313,12 +592,8 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new().negated_character_class("abc").build();
     /// assert_eq!(regex, "[^abc]");
     /// ```
-    pub fn negated_character_class(&mut self, chars: &str) -> Self {
-        self.value.push('[');
-        self.value.push('^');
-        self.value.push_str(chars);
-        self.value.push(']');
-        self.clone()
+    pub fn negated_character_class(self, chars: &str) -> Self {
+        self.push(Node::Class(Class::Set(ClassSet::parse(true, chars))))
     }
     
     /// Appends a range character class to the regular expression.
@@ -331,13 +606,11 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new().range_character_class('a', 'z').build();
     /// assert_eq!(regex, "[a-z]");
     /// ```
-    pub fn range_character_class(&mut self, start: char, end: char) -> Self {
-        self.value.push('[');
-        self.value.push(start);
-        self.value.push('-');
-        self.value.push(end);
-        self.value.push(']');
-        self.clone()
+    pub fn range_character_class(self, start: char, end: char) -> Self {
+        self.push(Node::Class(Class::Set(ClassSet {
+            negated: false,
+            items: vec![ClassItem::Range(start, end)],
+        })))
     }
     
     /// Appends a digit character class to the regular expression.
@@ -350,9 +623,8 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new().digit().build();
     /// assert_eq!(regex, "\\d");
     /// ```
-    pub fn digit(&mut self) -> Self {
-        self.value.push_str("\\d");
-        self.clone()
+    pub fn digit(self) -> Self {
+        self.push(Node::Class(Class::Perl(PerlClass::Digit)))
     }
     
     /// Appends a non-digit character class to the regular expression.
@@ -365,9 +637,8 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new().non_digit().build();
     /// assert_eq!(regex, "\\D");
     /// ```
-    pub fn non_digit(&mut self) -> Self {
-        self.value.push_str("\\D");
-        self.clone()
+    pub fn non_digit(self) -> Self {
+        self.push(Node::Class(Class::Perl(PerlClass::NonDigit)))
     }
     
     /// Appends a word character class to the regular expression.
@@ -380,9 +651,8 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new().word_character().build();
     /// assert_eq!(regex, "\\w");
     /// ```
-    pub fn word_character(&mut self) -> Self {
-        self.value.push_str("\\w");
-        self.clone()
+    pub fn word_character(self) -> Self {
+        self.push(Node::Class(Class::Perl(PerlClass::Word)))
     }
     
     /// Appends a non-word character class to the regular expression.
@@ -395,9 +665,8 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new().non_word_character().build();
     /// assert_eq!(regex, "\\W");
     /// ```
-    pub fn non_word_character(&mut self) -> Self {
-        self.value.push_str("\\W");
-        self.clone()
+    pub fn non_word_character(self) -> Self {
+        self.push(Node::Class(Class::Perl(PerlClass::NonWord)))
     }
     
     /// Appends a whitespace character class to the regular expression.
@@ -410,9 +679,8 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new().whitespace().build();
     /// assert_eq!(regex, "\\s");
     /// ```
-    pub fn whitespace(&mut self) -> Self {
-        self.value.push_str("\\s");
-        self.clone()
+    pub fn whitespace(self) -> Self {
+        self.push(Node::Class(Class::Perl(PerlClass::Whitespace)))
     }
     
     /// Appends a non-whitespace character class to the regular expression.
@@ -425,9 +693,8 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new().non_whitespace().build();
     /// assert_eq!(regex, "\\S");
     /// ```
-    pub fn non_whitespace(&mut self) -> Self {
-        self.value.push_str("\\S");
-        self.clone()
+    pub fn non_whitespace(self) -> Self {
+        self.push(Node::Class(Class::Perl(PerlClass::NonWhitespace)))
     }
     
     /// Appends a zero or more quantifier to the regular expression.
@@ -442,10 +709,12 @@ impl RegexBuilder {
     ///     .build();
     /// assert_eq!(regex, "[a]*");
     /// ```
-    pub fn zero_or_more(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str(&regex.build());
-        self.value.push('*');
-        self.clone()
+    pub fn zero_or_more(self, regex: RegexBuilder) -> Self {
+        self.push(Node::Repeat {
+            node: Box::new(regex.into_node()),
+            repetition: Repetition::ZeroOrMore,
+            greedy: true,
+        })
     }
     
     /// Appends a one or more quantifier to the regular expression.
@@ -459,11 +728,20 @@ impl RegexBuilder {
     ///     .one_or_more(RegexBuilder::new().character_class("a"))
     ///     .build();
     /// assert_eq!(regex, "[a]+");
-    /// ```
-    pub fn one_or_more(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str(&regex.build());
-        self.value.push('+');
-        self.clone()
+    ///
+    /// // Patterns longer than a single atom are grouped, so the quantifier
+    /// // applies to all of them.
+    /// let regex = RegexBuilder::new()
+    ///     .one_or_more(RegexBuilder::new().string("ab"))
+    ///     .build();
+    /// assert_eq!(regex, "(?:ab)+");
+    /// ```
+    pub fn one_or_more(self, regex: RegexBuilder) -> Self {
+        self.push(Node::Repeat {
+            node: Box::new(regex.into_node()),
+            repetition: Repetition::OneOrMore,
+            greedy: true,
+        })
     }
     
     /// Appends a zero or one quantifier to the regular expression.
@@ -478,10 +756,12 @@ impl RegexBuilder {
     ///     .build();
     /// assert_eq!(regex, "[a]?");
     /// ```
-    pub fn zero_or_one(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str(&regex.build());
-        self.value.push('?');
-        self.clone()
+    pub fn zero_or_one(self, regex: RegexBuilder) -> Self {
+        self.push(Node::Repeat {
+            node: Box::new(regex.into_node()),
+            repetition: Repetition::ZeroOrOne,
+            greedy: true,
+        })
     }
     
     /// Appends an exact repetitions quantifier to the regular expression.
@@ -496,9 +776,12 @@ impl RegexBuilder {
     ///     .build();
     /// assert_eq!(regex, "\\d{3}");
     /// ```
-    pub fn exact_repetitions(&mut self, regex: RegexBuilder, n: usize) -> Self {
-        self.value.push_str(&format!("{}{{{}}}", regex.build(), n));
-        self.clone()
+    pub fn exact_repetitions(self, regex: RegexBuilder, n: usize) -> Self {
+        self.push(Node::Repeat {
+            node: Box::new(regex.into_node()),
+            repetition: Repetition::Exactly(n),
+            greedy: true,
+        })
     }
     
     /// Appends a minimum repetitions quantifier to the regular expression.
@@ -513,9 +796,12 @@ impl RegexBuilder {
     ///     .build();
     /// assert_eq!(regex, "\\d{3,}");
     /// ```
-    pub fn min_repetitions(&mut self, regex: RegexBuilder, n: usize) -> Self {
-        self.value.push_str(&format!("{}{{{},}}", regex.build(), n));
-        self.clone()
+    pub fn min_repetitions(self, regex: RegexBuilder, n: usize) -> Self {
+        self.push(Node::Repeat {
+            node: Box::new(regex.into_node()),
+            repetition: Repetition::AtLeast(n),
+            greedy: true,
+        })
     }
     
     /// Appends a range repetitions quantifier to the regular expression.
@@ -529,10 +815,64 @@ impl RegexBuilder {
     ///     .range_repetitions(RegexBuilder::new().digit(), 3, 5)
     ///     .build();
     /// assert_eq!(regex, "\\d{3,5}");
+    ///
+    /// // A maximum below the minimum does not compile, whatever the engine.
+    /// let reversed = RegexBuilder::new().range_repetitions(RegexBuilder::new().digit(), 5, 2);
+    /// assert!(reversed.to_regex().is_err());
+    /// assert!(reversed.compile::<simple_regex::backtrack::Regex>().is_err());
+    /// ```
+    pub fn range_repetitions(self, regex: RegexBuilder, n: usize, m: usize) -> Self {
+        self.push(Node::Repeat {
+            node: Box::new(regex.into_node()),
+            repetition: Repetition::Between(n, m),
+            greedy: true,
+        })
+    }
+
+    /// Appends the given regular expression with its quantifiers made lazy, so that they
+    /// match as few repetitions as possible. Only quantifiers appended directly to
+    /// `regex` are affected, not those nested inside them.
+    ///
+    /// # Example
+    ///
     /// ```
-    pub fn range_repetitions(&mut self, regex: RegexBuilder, n: usize, m: usize) -> Self {
-        self.value.push_str(&format!("{}{{{},{}}}", regex.build(), n, m));
-        self.clone()
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let regex = RegexBuilder::new()
+    ///     .lazy(RegexBuilder::new().one_or_more(RegexBuilder::new().dot()))
+    ///     .literal(',')
+    ///     .build();
+    /// assert_eq!(regex, ".+?,");
+    /// ```
+    pub fn lazy(mut self, regex: RegexBuilder) -> Self {
+        for node in regex.nodes {
+            self.nodes.push(match node {
+                Node::Repeat { node, repetition, .. } => Node::Repeat {
+                    node,
+                    repetition,
+                    greedy: false,
+                },
+                node => node,
+            });
+        }
+        self
+    }
+    
+    /// Appends the patterns of another builder, so that builders for common pieces can
+    /// be reused without wrapping them in a group.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let year = RegexBuilder::new().exact_repetitions(RegexBuilder::new().digit(), 4);
+    /// let regex = RegexBuilder::new().literal('(').then(year).literal(')').build();
+    /// assert_eq!(regex, "\\(\\d{4}\\)");
+    /// ```
+    pub fn then(mut self, regex: RegexBuilder) -> Self {
+        self.nodes.extend(regex.nodes);
+        self
     }
     
     /// Appends a group to the regular expression.
@@ -547,11 +887,11 @@ impl RegexBuilder {
     ///     .build();
     /// assert_eq!(regex, "(?:[ab])");
     /// ```
-    pub fn group(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str("(?:");
-        self.value.push_str(&regex.build());
-        self.value.push(')');
-        self.clone()
+    pub fn group(self, regex: RegexBuilder) -> Self {
+        self.push(Node::Group {
+            kind: GroupKind::NonCapturing,
+            node: Box::new(regex.into_node()),
+        })
     }
     
     /// Appends a backreference to a capturing group in the regular expression.
@@ -566,10 +906,35 @@ impl RegexBuilder {
     ///     .backreference(1)
     ///     .build();
     /// assert_eq!(regex, "([ab])\\1");
+    ///
+    /// // A backreference to a group that does not exist never matches.
+    /// let matcher = RegexBuilder::new().literal('a').backreference(3).to_matcher().unwrap();
+    /// assert!(!matcher.is_match("aa"));
+    /// ```
+    pub fn backreference(self, group_number: usize) -> Self {
+        self.push(Node::Backreference(group_number))
+    }
+    
+    /// Appends a backreference to a named capturing group in the regular expression.
+    ///
+    /// Compiling the builder fails if it has no group of that name.
+    ///
+    /// # Example
+    ///
     /// ```
-    pub fn backreference(&mut self, group_number: usize) -> Self {
-        self.value.push_str(&format!("\\{}", group_number));
-        self.clone()
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let (builder, quote) = RegexBuilder::new().named_group("quote", RegexBuilder::new().character_class("'\""));
+    /// let builder = builder.one_or_more(RegexBuilder::new().word_character()).backreference_to(&quote);
+    /// assert_eq!(builder.build(), "(?P<quote>['\"])\\w+\\k<quote>");
+    /// assert!(builder.to_matcher().unwrap().is_match("'word'"));
+    ///
+    /// // The handle belongs to the builder that created the group.
+    /// let error = RegexBuilder::new().literal('a').backreference_to(&quote).to_matcher().unwrap_err();
+    /// assert_eq!(error.message(), "backreference to unknown capture group `quote`");
+    /// ```
+    pub fn backreference_to(self, group: &Group) -> Self {
+        self.push(Node::NamedBackreference(group.name().to_string()))
     }
     
     /// Appends a word boundary anchor (\b) to the regular expression.
@@ -582,9 +947,8 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new().word_boundary().build();
     /// assert_eq!(regex, "\\b");
     /// ```
-    pub fn word_boundary(&mut self) -> Self {
-        self.value.push_str("\\b");
-        self.clone()
+    pub fn word_boundary(self) -> Self {
+        self.push(Node::Anchor(Anchor::WordBoundary))
     }
     
     /// Appends a non-word boundary anchor (\B) to the regular expression.
@@ -597,11 +961,36 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new().non_word_boundary().build();
     /// assert_eq!(regex, "\\B");
     /// ```
-    pub fn non_word_boundary(&mut self) -> Self {
-        self.value.push_str("\\B");
-        self.clone()
+    pub fn non_word_boundary(self) -> Self {
+        self.push(Node::Anchor(Anchor::NonWordBoundary))
     }
     
+    /// Appends a pattern with the given inline flags enabled, `(?flags:...)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    /// use simple_regex::ast::Flag;
+    ///
+    /// let regex = RegexBuilder::new()
+    ///     .with_flags([Flag::CaseInsensitive, Flag::DotMatchesNewLine], RegexBuilder::new().character_class("a"))
+    ///     .build();
+    /// assert_eq!(regex, "(?is:[a])");
+    /// ```
+    pub fn with_flags<I>(self, flags: I, regex: RegexBuilder) -> Self
+    where
+        I: IntoIterator<Item = Flag>,
+    {
+        self.push(Node::Flags {
+            flags: Flags {
+                enabled: flags.into_iter().collect(),
+                disabled: Vec::new(),
+            },
+            node: Box::new(regex.into_node()),
+        })
+    }
+
     /// Appends a case-insensitive modifier to the regular expression.
     ///
     /// # Example
@@ -612,28 +1001,29 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new()
     ///     .case_insensitive(RegexBuilder::new().character_class("a"))
     ///     .build();
-    /// assert_eq!(regex, "(?i[a])");
+    /// assert_eq!(regex, "(?i:[a])");
     /// ```
-    pub fn case_insensitive(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str(&format!("(?i[{}]", regex.build()));
-        self.clone()
+    pub fn case_insensitive(self, regex: RegexBuilder) -> Self {
+        self.with_flags([Flag::CaseInsensitive], regex)
     }
     
-    /// Appends a global search modifier to the regular expression.
+    /// Appends the given pattern unchanged.
+    ///
+    /// Global search is not a pattern flag: compile the builder with `to_regex` and use
+    /// `Regex::find_iter` or `Regex::captures_iter` to find every match.
     ///
     /// # Example
     ///
     /// ```
     /// use simple_regex::RegexBuilder;
     ///
-    /// let regex = RegexBuilder::new()
-    ///     .global_search(RegexBuilder::new().character_class("a"))
-    ///     .build();
-    /// assert_eq!(regex, "(?g[a])");
+    /// let regex = RegexBuilder::new().digit().to_regex_or_panic();
+    /// let digits: Vec<&str> = regex.find_iter("a1b2c3").map(|m| m.as_str()).collect();
+    /// assert_eq!(digits, ["1", "2", "3"]);
     /// ```
-    pub fn global_search(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str(&format!("(?g[{}]", regex.build()));
-        self.clone()
+    #[deprecated(note = "global search is not a pattern flag; use `Regex::find_iter` instead")]
+    pub fn global_search(self, regex: RegexBuilder) -> Self {
+        self.push(regex.into_node())
     }
     
     /// Appends a multiline modifier to the regular expression.
@@ -646,11 +1036,10 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new()
     ///     .multiline(RegexBuilder::new().character_class("a"))
     ///     .build();
-    /// assert_eq!(regex, "(?m[a])");
+    /// assert_eq!(regex, "(?m:[a])");
     /// ```
-    pub fn multiline(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str(&format!("(?m[{}]", regex.build()));
-        self.clone()
+    pub fn multiline(self, regex: RegexBuilder) -> Self {
+        self.with_flags([Flag::MultiLine], regex)
     }
     
     /// Appends a dot-all modifier to the regular expression, allowing '.' to match newline characters.
@@ -663,11 +1052,66 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new()
     ///     .dot_all(RegexBuilder::new().character_class("a"))
     ///     .build();
-    /// assert_eq!(regex, "(?s[a])");
+    /// assert_eq!(regex, "(?s:[a])");
     /// ```
-    pub fn dot_all(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str(&format!("(?s[{}]", regex.build()));
-        self.clone()
+    pub fn dot_all(self, regex: RegexBuilder) -> Self {
+        self.with_flags([Flag::DotMatchesNewLine], regex)
+    }
+
+    /// Appends an ignore-whitespace modifier to the regular expression, allowing whitespace and `#` comments in it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let regex = RegexBuilder::new()
+    ///     .ignore_whitespace(RegexBuilder::new().character_class("a"))
+    ///     .build();
+    /// assert_eq!(regex, "(?x:[a])");
+    ///
+    /// // Literal whitespace and `#` are escaped, so they still match.
+    /// let regex = RegexBuilder::new()
+    ///     .ignore_whitespace(RegexBuilder::new().string("a b#").character_class(" #"))
+    ///     .to_regex()
+    ///     .unwrap();
+    /// assert_eq!(regex.as_str(), "(?x:a\\ b\\#[\\ \\#])");
+    /// assert!(regex.is_match("a b# "));
+    /// ```
+    pub fn ignore_whitespace(self, regex: RegexBuilder) -> Self {
+        self.with_flags([Flag::IgnoreWhitespace], regex)
+    }
+
+    /// Appends a swap-greed modifier to the regular expression, making quantifiers lazy by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let regex = RegexBuilder::new()
+    ///     .swap_greed(RegexBuilder::new().one_or_more(RegexBuilder::new().digit()))
+    ///     .build();
+    /// assert_eq!(regex, "(?U:\\d+)");
+    /// ```
+    pub fn swap_greed(self, regex: RegexBuilder) -> Self {
+        self.with_flags([Flag::SwapGreed], regex)
+    }
+
+    /// Appends a CRLF modifier to the regular expression, so that `^` and `$` in multiline mode also treat `\r\n` as a line terminator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let regex = RegexBuilder::new()
+    ///     .crlf(RegexBuilder::new().multiline(RegexBuilder::new().end_of_line()))
+    ///     .build();
+    /// assert_eq!(regex, "(?R:(?m:$))");
+    /// ```
+    pub fn crlf(self, regex: RegexBuilder) -> Self {
+        self.with_flags([Flag::Crlf], regex)
     }
     
     /// Appends an alternative (|) to the regular expression, allowing either of the provided patterns to match.
@@ -681,10 +1125,44 @@ impl RegexBuilder {
     ///     .alternative(RegexBuilder::new().character_class("a"), RegexBuilder::new().character_class("b"))
     ///     .build();
     /// assert_eq!(regex, "[a]|[b]");
+    ///
+    /// // Next to other patterns the alternation is grouped.
+    /// let regex = RegexBuilder::new()
+    ///     .character_class("x")
+    ///     .alternative(RegexBuilder::new().character_class("a"), RegexBuilder::new().character_class("b"))
+    ///     .build();
+    /// assert_eq!(regex, "[x](?:[a]|[b])");
+    /// ```
+    pub fn alternative(self, regex1: RegexBuilder, regex2: RegexBuilder) -> Self {
+        self.any_of([regex1, regex2])
+    }
+
+    /// Appends an alternation of any number of patterns, allowing any one of them to match.
+    ///
+    /// Branches that are alternations themselves are flattened into this one.
+    ///
+    /// # Example
+    ///
     /// ```
-    pub fn alternative(&mut self, regex1: RegexBuilder, regex2: RegexBuilder) -> Self {
-        self.value.push_str(&format!("{}|{}", regex1.build(), regex2.build()));
-        self.clone()
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let regex = RegexBuilder::new()
+    ///     .start_of_line()
+    ///     .any_of([
+    ///         RegexBuilder::new().string("cat"),
+    ///         RegexBuilder::new().string("dog"),
+    ///         RegexBuilder::new().digit(),
+    ///     ])
+    ///     .end_of_line()
+    ///     .build();
+    /// assert_eq!(regex, "^(?:cat|dog|\\d)$");
+    /// ```
+    pub fn any_of<I>(self, regexes: I) -> Self
+    where
+        I: IntoIterator<Item = RegexBuilder>,
+    {
+        let branches = regexes.into_iter().map(RegexBuilder::into_node).collect();
+        self.push(Node::alternation(branches))
     }
     
     /// Appends a capturing group to the regular expression.
@@ -699,11 +1177,68 @@ impl RegexBuilder {
     ///     .build();
     /// assert_eq!(regex, "([ab])");
     /// ```
-    pub fn capturing_group(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push('(');
-        self.value.push_str(&regex.build());
-        self.value.push(')');
-        self.clone()
+    pub fn capturing_group(self, regex: RegexBuilder) -> Self {
+        self.push(Node::Group {
+            kind: GroupKind::Capturing,
+            node: Box::new(regex.into_node()),
+        })
+    }
+    
+    /// Appends a named capturing group to the regular expression, and returns a
+    /// [`Group`] handle to it along with the builder.
+    ///
+    /// Compiling the builder fails if another group has the same name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let (builder, year) = RegexBuilder::new()
+    ///     .named_group("year", RegexBuilder::new().exact_repetitions(RegexBuilder::new().digit(), 4));
+    /// assert_eq!(builder.build(), "(?P<year>\\d{4})");
+    ///
+    /// let captures = builder.to_regex().unwrap().captures("in 2024").unwrap();
+    /// assert_eq!(year.as_str(&captures), Some("2024"));
+    ///
+    /// let (builder, _) = builder.literal('-').named_group("year", RegexBuilder::new().digit());
+    /// let error = builder.to_regex().unwrap_err();
+    /// assert_eq!(error.message(), "duplicate capture group name `year`");
+    /// assert_eq!(error.span(), Some(16..28));
+    /// ```
+    pub fn named_group(self, name: &str, regex: RegexBuilder) -> (Self, Group) {
+        let builder = self.push(Node::Group {
+            kind: GroupKind::Named(name.to_string()),
+            node: Box::new(regex.into_node()),
+        });
+        (builder, Group::new(name))
+    }
+    
+    /// Returns a handle to the named capturing group `name`, if the builder has one,
+    /// such as a group of a pattern read with [`RegexBuilder::parse`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let builder = RegexBuilder::parse(r"(?P<year>\d{4})-(?P<month>\d{2})").unwrap();
+    /// let month = builder.named_group_handle("month").unwrap();
+    /// assert!(builder.named_group_handle("day").is_none());
+    ///
+    /// let captures = builder.to_regex().unwrap().captures("2024-05").unwrap();
+    /// assert_eq!(month.as_str(&captures), Some("05"));
+    /// ```
+    pub fn named_group_handle(&self, name: &str) -> Option<Group> {
+        let mut found = false;
+        self.nodes.iter().for_each(|node| {
+            node.walk(&mut |node| {
+                if let Node::Group { kind: GroupKind::Named(group), .. } = node {
+                    found |= group == name;
+                }
+            })
+        });
+        found.then(|| Group::new(name))
     }
     
     /// Appends a non-capturing group to the regular expression.
@@ -718,11 +1253,11 @@ impl RegexBuilder {
     ///     .build();
     /// assert_eq!(regex, "(?:[ab])");
     /// ```
-    pub fn non_capturing_group(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str("(?:");
-        self.value.push_str(&regex.build());
-        self.value.push(')');
-        self.clone()
+    pub fn non_capturing_group(self, regex: RegexBuilder) -> Self {
+        self.push(Node::Group {
+            kind: GroupKind::NonCapturing,
+            node: Box::new(regex.into_node()),
+        })
     }
     
     /// Appends a word boundary anchor (\b) to the regular expression, asserting the position between a word character and a non-word character.
@@ -737,9 +1272,10 @@ impl RegexBuilder {
     ///     .build();
     /// assert_eq!(regex, "\\b[a]\\b");
     /// ```
-    pub fn bound_word(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str(&format!("\\b[{}]\\b", regex.build()));
-        self.clone()
+    pub fn bound_word(mut self, regex: RegexBuilder) -> Self {
+        self.nodes.push(Node::Anchor(Anchor::WordBoundary));
+        self.nodes.push(regex.into_node());
+        self.push(Node::Anchor(Anchor::WordBoundary))
     }
     
     /// Appends a negative word boundary anchor (\B) to the regular expression, asserting a position where a word character is not followed by another word character.
@@ -754,9 +1290,10 @@ impl RegexBuilder {
     ///     .build();
     /// assert_eq!(regex, "\\B[a]\\B");
     /// ```
-    pub fn negative_word_boundary(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str(&format!("\\B[{}]\\B", regex.build()));
-        self.clone()
+    pub fn negative_word_boundary(mut self, regex: RegexBuilder) -> Self {
+        self.nodes.push(Node::Anchor(Anchor::NonWordBoundary));
+        self.nodes.push(regex.into_node());
+        self.push(Node::Anchor(Anchor::NonWordBoundary))
     }
     
     /// Appends a positive lookahead assertion to the regular expression, asserting that the given pattern can match next at the current position.
@@ -771,9 +1308,11 @@ impl RegexBuilder {
     ///     .build();
     /// assert_eq!(regex, "(?=[a])");
     /// ```
-    pub fn positive_lookahead(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str(&format!("(?={})", regex.build()));
-        self.clone()
+    pub fn positive_lookahead(self, regex: RegexBuilder) -> Self {
+        self.push(Node::Look {
+            kind: LookKind::PositiveLookahead,
+            node: Box::new(regex.into_node()),
+        })
     }
     
     /// Appends a negative lookahead assertion to the regular expression, asserting that the given pattern cannot match next at the current position.
@@ -788,9 +1327,11 @@ impl RegexBuilder {
     ///     .build();
     /// assert_eq!(regex, "(?![a])");
     /// ```
-    pub fn negative_lookahead(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str(&format!("(?!{})", regex.build()));
-        self.clone()
+    pub fn negative_lookahead(self, regex: RegexBuilder) -> Self {
+        self.push(Node::Look {
+            kind: LookKind::NegativeLookahead,
+            node: Box::new(regex.into_node()),
+        })
     }
     
     /// Appends a positive lookbehind assertion to the regular expression, asserting that the given pattern can match preceding at the current position.
@@ -803,11 +1344,13 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new()
     ///     .positive_lookbehind(RegexBuilder::new().character_class("a"))
     ///     .build();
-    /// assert_eq!(regex, "(?<=a)");
+    /// assert_eq!(regex, "(?<=[a])");
     /// ```
-    pub fn positive_lookbehind(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str(&format!("(?<={})", regex.build()));
-        self.clone()
+    pub fn positive_lookbehind(self, regex: RegexBuilder) -> Self {
+        self.push(Node::Look {
+            kind: LookKind::PositiveLookbehind,
+            node: Box::new(regex.into_node()),
+        })
     }
     
     /// Appends a negative lookbehind assertion to the regular expression, asserting that the given pattern cannot match preceding at the current position.
@@ -820,11 +1363,13 @@ impl RegexBuilder {
     /// let regex = RegexBuilder::new()
     ///     .negative_lookbehind(RegexBuilder::new().character_class("a"))
     ///     .build();
-    /// assert_eq!(regex, "(?<!a)");
+    /// assert_eq!(regex, "(?<![a])");
     /// ```
-    pub fn negative_lookbehind(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str(&format!("(?<!{})", regex.build()));
-        self.clone()
+    pub fn negative_lookbehind(self, regex: RegexBuilder) -> Self {
+        self.push(Node::Look {
+            kind: LookKind::NegativeLookbehind,
+            node: Box::new(regex.into_node()),
+        })
     }
     
     /// Appends an optional pattern to the regular expression, allowing the given pattern to match zero or one time.
@@ -839,9 +1384,15 @@ impl RegexBuilder {
     ///     .build();
     /// assert_eq!(regex, "(a)?");
     /// ```
-    pub fn optional(&mut self, regex: RegexBuilder) -> Self {
-        self.value.push_str(&format!("({})?", regex.build()));
-        self.clone()
+    pub fn optional(self, regex: RegexBuilder) -> Self {
+        self.push(Node::Repeat {
+            node: Box::new(Node::Group {
+                kind: GroupKind::Capturing,
+                node: Box::new(regex.into_node()),
+            }),
+            repetition: Repetition::ZeroOrOne,
+            greedy: true,
+        })
     }
 
     /// Appends to the regular expression the character class `[-\s]`.
@@ -849,7 +1400,7 @@ impl RegexBuilder {
     /// # Example
     ///
     /// ```
-    /// use my_regex_builder::RegexBuilder;
+    /// use simple_regex::RegexBuilder;
     ///
     /// let regex = RegexBuilder::new()
     ///     .literal('-')
@@ -858,9 +1409,11 @@ impl RegexBuilder {
     ///     .build();
     /// assert_eq!(regex, "-[-\\s]a");
     /// ```
-    pub fn dash_space_character_class(&mut self) -> Self {
-        self.value.push_str("[-\\s]");
-        self.clone()
+    pub fn dash_space_character_class(self) -> Self {
+        self.push(Node::Class(Class::Set(ClassSet {
+            negated: false,
+            items: vec![ClassItem::Char('-'), ClassItem::Perl(PerlClass::Whitespace)],
+        })))
     }
     
     /// Builds the regular expression as a string.
@@ -875,9 +1428,231 @@ impl RegexBuilder {
     ///     .character_class("bc")
     ///     .zero_or_more(RegexBuilder::new().digit())
     ///     .build();
-    /// assert_eq!(regex, "a[bc]*\\d*");
+    /// assert_eq!(regex, "a[bc]\\d*");
     /// ```
     pub fn build(&self) -> String {
-        self.value.clone()
+        ast::render(&self.nodes, None)
+    }
+
+    /// Builds the regular expression like [`RegexBuilder::build`], colored for terminal
+    /// output with the [`ansi`] module.
+    ///
+    /// Literals, escapes, classes, anchors and quantifiers each get a color of their own,
+    /// and matching parentheses share a color that rotates with their nesting depth.
+    /// The colors follow the current [`ansi::ColorChoice`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::{ansi, RegexBuilder};
+    ///
+    /// ansi::set_color_choice(ansi::ColorChoice::Always);
+    /// let colored = RegexBuilder::new()
+    ///     .start_of_line()
+    ///     .one_or_more(RegexBuilder::new().digit())
+    ///     .to_colored_string();
+    /// assert_eq!(colored, "\x1b[31m^\x1b[0m\x1b[32m\\d\x1b[0m\x1b[35m+\x1b[0m");
+    /// println!("{}", colored);
+    /// ```
+    pub fn to_colored_string(&self) -> String {
+        highlight::highlight(&self.nodes)
+    }
+
+    /// Returns Rust code that rebuilds the current `RegexBuilder` as a chain of builder
+    /// method calls, laid out in the style of rustfmt.
+    ///
+    /// Together with [`RegexBuilder::parse`], this turns a pattern string into builder
+    /// code. Since [`named_group`](RegexBuilder::named_group) returns a group handle
+    /// along with the builder, each named group ends the chain with a `let` statement
+    /// binding both, and backreferences to the group go through the handle. Constructs
+    /// that builder calls cannot express, such as disabled flags or a backreference
+    /// inside the group it refers to, are emitted as [`raw`](RegexBuilder::raw) calls.
+    /// Code that uses `with_flags` needs `simple_regex::ast::Flag` in scope.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let code = RegexBuilder::parse(r"^\d{3}(?:-\d{4})?$").unwrap().to_rust_code();
+    /// assert_eq!(
+    ///     code,
+    ///     "RegexBuilder::new()
+    ///     .start_of_line()
+    ///     .exact_repetitions(RegexBuilder::new().digit(), 3)
+    ///     .zero_or_one(
+    ///         RegexBuilder::new().group(
+    ///             RegexBuilder::new()
+    ///                 .literal('-')
+    ///                 .exact_repetitions(RegexBuilder::new().digit(), 4),
+    ///         ),
+    ///     )
+    ///     .end_of_line()"
+    /// );
+    ///
+    /// let code = RegexBuilder::parse(r"(?<y>\d+)-\k<y>").unwrap().to_rust_code();
+    /// assert_eq!(
+    ///     code,
+    ///     r#"let (builder, y) = RegexBuilder::new().named_group(
+    ///     "y",
+    ///     RegexBuilder::new().one_or_more(RegexBuilder::new().digit()),
+    /// );
+    /// builder.literal('-').backreference_to(&y)"#
+    /// );
+    /// let matcher = {
+    ///     let (builder, y) = RegexBuilder::new().named_group(
+    ///         "y",
+    ///         RegexBuilder::new().one_or_more(RegexBuilder::new().digit()),
+    ///     );
+    ///     builder.literal('-').backreference_to(&y)
+    /// }
+    /// .to_matcher()
+    /// .unwrap();
+    /// assert!(matcher.is_match("12-12"));
+    /// assert!(!matcher.is_match("12-13"));
+    /// ```
+    pub fn to_rust_code(&self) -> String {
+        codegen::rust_code(&self.nodes)
+    }
+
+    /// Describes the current `RegexBuilder` in plain English, one piece per line,
+    /// naming the builder method that appended each piece in parentheses.
+    ///
+    /// The contents of groups, quantifiers and alternatives are indented below them,
+    /// and alternatives are marked with `-`. The text can be pasted into a doc comment
+    /// next to the builder.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let builder = RegexBuilder::new()
+    ///     .start_of_line()
+    ///     .zero_or_more(RegexBuilder::new().whitespace())
+    ///     .any_of([
+    ///         RegexBuilder::new().exact_repetitions(RegexBuilder::new().digit(), 3),
+    ///         RegexBuilder::new().literal('+').character_class("0-9a"),
+    ///     ]);
+    /// assert_eq!(
+    ///     builder.explain(),
+    ///     "start of line (start_of_line)
+    /// then zero or more times (zero_or_more):
+    ///   a whitespace character (whitespace)
+    /// then one of (any_of):
+    ///   - exactly 3 times (exact_repetitions):
+    ///       a digit (digit)
+    ///   - the character '+' (literal)
+    ///     then one of '0' to '9' or 'a' (character_class)"
+    /// );
+    /// ```
+    pub fn explain(&self) -> String {
+        explain::explain(&self.nodes)
+    }
+
+    /// Computes static facts about the matches of the pattern: their minimum and maximum
+    /// length, whether the empty string matches, whether matches are anchored at the start
+    /// and end of the haystack, and which characters they can start with.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let analysis = RegexBuilder::new()
+    ///     .one_or_more(RegexBuilder::new().character_class("a-z"))
+    ///     .literal('@')
+    ///     .zero_or_one(RegexBuilder::new().string("example.com"))
+    ///     .analyze();
+    /// assert_eq!((analysis.min_len, analysis.max_len), (2, None));
+    /// assert!(!analysis.nullable);
+    /// assert!(!analysis.anchored_start && !analysis.anchored_end);
+    /// assert_eq!(analysis.first_chars.unwrap().to_string(), "[a-z]");
+    /// ```
+    pub fn analyze(&self) -> analysis::Analysis {
+        analysis::Analysis::new(self)
+    }
+
+    /// Compiles the current `RegexBuilder` into the [`Language`](language::Language) of
+    /// the strings it matches as a whole, which can be counted and listed.
+    ///
+    /// Backreferences, look-around assertions, word boundaries and multi-line anchors
+    /// are reported as a [`BuildError`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    /// use simple_regex::language::Count;
+    ///
+    /// let hex_byte = RegexBuilder::new().exact_repetitions(RegexBuilder::new().character_class("0-9a-f"), 2);
+    /// let language = hex_byte.language().unwrap();
+    /// assert_eq!(language.count(), Count::Finite(256));
+    /// assert_eq!(language.strings().nth(16).unwrap(), "10");
+    ///
+    /// let error = RegexBuilder::new().word_boundary().language().unwrap_err();
+    /// assert_eq!(error.method(), Some("word_boundary"));
+    /// ```
+    pub fn language(&self) -> Result<language::Language, BuildError> {
+        self.compile()
+    }
+
+    /// Returns a random string that the whole pattern matches, or `None` if there is none.
+    ///
+    /// Unbounded repetitions are capped at [`DEFAULT_REPEAT_LIMIT`](generate::DEFAULT_REPEAT_LIMIT)
+    /// repetitions beyond their minimum; use [`generator`](RegexBuilder::generator) to
+    /// configure the cap. Requires the `rand` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let builder = RegexBuilder::new()
+    ///     .start_of_line()
+    ///     .any_of([RegexBuilder::new().string("GET"), RegexBuilder::new().string("POST")])
+    ///     .literal(' ')
+    ///     .literal('/')
+    ///     .zero_or_more(RegexBuilder::new().character_class("a-z0-9/"))
+    ///     .end_of_line();
+    /// let regex = builder.to_regex().unwrap();
+    /// let mut rng = rand::thread_rng();
+    /// for _ in 0..100 {
+    ///     let request = builder.generate(&mut rng).unwrap();
+    ///     assert!(regex.is_match(&request), "{:?}", request);
+    /// }
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn generate<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Option<String> {
+        self.generator().generate(rng)
+    }
+
+    /// Returns a [`Generator`](generate::Generator) of random strings matching the
+    /// pattern, with configurable caps. Requires the `rand` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_regex::RegexBuilder;
+    ///
+    /// let builder = RegexBuilder::new().zero_or_more(RegexBuilder::new().literal('a'));
+    /// let generator = builder.generator().with_repeat_limit(2);
+    /// let mut rng = rand::thread_rng();
+    /// for _ in 0..100 {
+    ///     assert!(generator.generate(&mut rng).unwrap().len() <= 2);
+    /// }
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn generator(&self) -> generate::Generator {
+        generate::Generator::new(self)
+    }
+}
+
+impl std::str::FromStr for RegexBuilder {
+    type Err = ParseError;
+
+    /// Parses a pattern with [`RegexBuilder::parse`].
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        Self::parse(pattern)
     }
 }
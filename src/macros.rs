@@ -0,0 +1,235 @@
+//! The `pattern!` and `regex!` macros, which expand a compact pattern DSL into
+//! `RegexBuilder` calls.
+
+/// Builds a `RegexBuilder` from a compact DSL, expanding to the equivalent chain of
+/// builder calls.
+///
+/// The macro takes a comma-separated sequence of items, each optionally followed by
+/// a quantifier:
+///
+/// | Item | Builder call |
+/// | --- | --- |
+/// | `'c'`, `"text"` | `literal('c')`, `string("text")` |
+/// | `'a'..='z'` | `range_character_class('a', 'z')` |
+/// | `start`, `end` | `start_of_line()`, `end_of_line()` |
+/// | `any` | `dot()` |
+/// | `digit`, `word`, `ws` | `digit()`, `word_character()`, `whitespace()` |
+/// | `non_digit`, `non_word`, `non_ws` | `non_digit()`, `non_word_character()`, `non_whitespace()` |
+/// | `boundary`, `non_boundary` | `word_boundary()`, `non_word_boundary()` |
+/// | `dash_space` | `dash_space_character_class()` |
+/// | `one_of("abc")`, `none_of("abc")` | `character_class("abc")`, `negated_character_class("abc")` |
+/// | `raw("...")` | `raw("...")` |
+/// | `same_as(name)` | `backreference_to(...)` with a handle to the group `name` |
+/// | `seq![...]` | the items in sequence, grouped when quantified |
+/// | `any_of![a, b, ...]` | `any_of([...])`, with one branch per item |
+/// | `capture![...]`, `named![name: ...]` | `capturing_group(...)`, `named_group("name", ...).0` |
+/// | `ahead![...]`, `not_ahead![...]` | `positive_lookahead(...)`, `negative_lookahead(...)` |
+/// | `behind![...]`, `not_behind![...]` | `positive_lookbehind(...)`, `negative_lookbehind(...)` |
+///
+/// The quantifiers are `*`, `+`, `?`, `{n}`, `{n,}` and `{n,m}`, each made lazy by
+/// a following `?`. Items inside `any_of!` are separate branches, so a branch of
+/// several items is written `seq![...]`. An unknown item is a compile error.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::pattern;
+///
+/// let builder = pattern![
+///     start,
+///     named![year: digit{4}],
+///     '-',
+///     named![month: digit{2}],
+///     any_of!["Z", seq![one_of("+-"), digit{2}]]?,
+///     end,
+/// ];
+/// assert_eq!(builder.build(), r"^(?P<year>\d{4})-(?P<month>\d{2})(?:Z|[+-]\d{2})?$");
+/// ```
+#[macro_export]
+macro_rules! pattern {
+    ($($items:tt)*) => {
+        $crate::__pattern!(@seq ($crate::RegexBuilder::new()) $($items)*)
+    };
+}
+
+/// Compiles a [`pattern!`] once, on first use, and returns it as a `&'static regex::Regex`.
+///
+/// The compiled pattern is cached in a static [`LazyPattern`](crate::LazyPattern), so
+/// evaluating the macro again, from any thread, returns the same `Regex`.
+///
+/// Look-around items and `same_as`, which the `regex` crate does not support, are
+/// compile errors; build such patterns with [`pattern!`] and `RegexBuilder::to_matcher`.
+///
+/// # Panics
+///
+/// Panics on first use if the built pattern does not compile, which only happens
+/// with `raw` items.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::regex;
+///
+/// fn is_hex_color(text: &str) -> bool {
+///     regex![start, '#', one_of("0-9a-fA-F"){6}, end].is_match(text)
+/// }
+///
+/// assert!(is_hex_color("#1e90ff"));
+/// assert!(!is_hex_color("#1e90f"));
+/// ```
+///
+/// ```compile_fail
+/// use simple_regex::regex;
+///
+/// let regex = regex![named![a: digit], same_as(a)];
+/// ```
+#[macro_export]
+macro_rules! regex {
+    ($($items:tt)*) => {{
+        $crate::__pattern!(@check $($items)*);
+        static PATTERN: $crate::LazyPattern = $crate::LazyPattern::new(|| $crate::pattern!($($items)*));
+        PATTERN.regex()
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __pattern {
+    // A sequence, one item at a time.
+    (@seq ($b:expr)) => { $b };
+    (@seq ($b:expr) , $($rest:tt)*) => { $crate::__pattern!(@seq ($b) $($rest)*) };
+    (@seq ($b:expr) $lo:literal ..= $hi:literal $($rest:tt)*) => {
+        $crate::__pattern!(@quant ($b) ($lo ..= $hi) $($rest)*)
+    };
+    (@seq ($b:expr) $name:ident ! [ $($args:tt)* ] $($rest:tt)*) => {
+        $crate::__pattern!(@quant ($b) ($name ! [ $($args)* ]) $($rest)*)
+    };
+    (@seq ($b:expr) $name:ident ( $($args:tt)* ) $($rest:tt)*) => {
+        $crate::__pattern!(@quant ($b) ($name ( $($args)* )) $($rest)*)
+    };
+    (@seq ($b:expr) $item:tt $($rest:tt)*) => {
+        $crate::__pattern!(@quant ($b) ($item) $($rest)*)
+    };
+
+    // The quantifier following an item, if any.
+    (@quant ($b:expr) ($($item:tt)*) * $($rest:tt)*) => {
+        $crate::__pattern!(@lazy ($b) (zero_or_more $crate::__pattern!(@item $($item)*)) $($rest)*)
+    };
+    (@quant ($b:expr) ($($item:tt)*) + $($rest:tt)*) => {
+        $crate::__pattern!(@lazy ($b) (one_or_more $crate::__pattern!(@item $($item)*)) $($rest)*)
+    };
+    (@quant ($b:expr) ($($item:tt)*) ? $($rest:tt)*) => {
+        $crate::__pattern!(@lazy ($b) (zero_or_one $crate::__pattern!(@item $($item)*)) $($rest)*)
+    };
+    (@quant ($b:expr) ($($item:tt)*) { $n:literal } $($rest:tt)*) => {
+        $crate::__pattern!(@lazy ($b) (exact_repetitions $crate::__pattern!(@item $($item)*), $n) $($rest)*)
+    };
+    (@quant ($b:expr) ($($item:tt)*) { $n:literal , } $($rest:tt)*) => {
+        $crate::__pattern!(@lazy ($b) (min_repetitions $crate::__pattern!(@item $($item)*), $n) $($rest)*)
+    };
+    (@quant ($b:expr) ($($item:tt)*) { $n:literal , $m:literal } $($rest:tt)*) => {
+        $crate::__pattern!(@lazy ($b) (range_repetitions $crate::__pattern!(@item $($item)*), $n, $m) $($rest)*)
+    };
+    (@quant ($b:expr) ($($item:tt)*) $($rest:tt)*) => {
+        $crate::__pattern!(@seq ($crate::__pattern!(@append ($b) $($item)*)) $($rest)*)
+    };
+
+    // A `?` after a quantifier makes it lazy.
+    (@lazy ($b:expr) ($method:ident $($arg:expr),*) ? $($rest:tt)*) => {
+        $crate::__pattern!(@seq ($b.lazy($crate::RegexBuilder::new().$method($($arg),*))) $($rest)*)
+    };
+    (@lazy ($b:expr) ($method:ident $($arg:expr),*) $($rest:tt)*) => {
+        $crate::__pattern!(@seq ($b.$method($($arg),*)) $($rest)*)
+    };
+
+    // A single item, as a builder of its own.
+    (@item $($item:tt)*) => {
+        $crate::__pattern!(@append ($crate::RegexBuilder::new()) $($item)*)
+    };
+
+    // Appends a single item to a builder.
+    (@append ($b:expr) start) => { $b.start_of_line() };
+    (@append ($b:expr) end) => { $b.end_of_line() };
+    (@append ($b:expr) any) => { $b.dot() };
+    (@append ($b:expr) digit) => { $b.digit() };
+    (@append ($b:expr) non_digit) => { $b.non_digit() };
+    (@append ($b:expr) word) => { $b.word_character() };
+    (@append ($b:expr) non_word) => { $b.non_word_character() };
+    (@append ($b:expr) ws) => { $b.whitespace() };
+    (@append ($b:expr) non_ws) => { $b.non_whitespace() };
+    (@append ($b:expr) boundary) => { $b.word_boundary() };
+    (@append ($b:expr) non_boundary) => { $b.non_word_boundary() };
+    (@append ($b:expr) dash_space) => { $b.dash_space_character_class() };
+    (@append ($b:expr) one_of ( $chars:expr )) => { $b.character_class($chars) };
+    (@append ($b:expr) none_of ( $chars:expr )) => { $b.negated_character_class($chars) };
+    (@append ($b:expr) raw ( $pattern:expr )) => { $b.raw($pattern) };
+    (@append ($b:expr) same_as ( $name:ident )) => {
+        $b.backreference_to(&$crate::__private::group(stringify!($name)))
+    };
+    (@append ($b:expr) seq ! [ $($items:tt)* ]) => { $crate::__pattern!(@seq ($b) $($items)*) };
+    (@append ($b:expr) any_of ! [ $($branches:tt)* ]) => {
+        $b.any_of($crate::__pattern!(@branches [] [] $($branches)*))
+    };
+    (@append ($b:expr) capture ! [ $($items:tt)* ]) => { $b.capturing_group($crate::pattern!($($items)*)) };
+    (@append ($b:expr) named ! [ $name:ident : $($items:tt)* ]) => {
+        $b.named_group(stringify!($name), $crate::pattern!($($items)*)).0
+    };
+    (@append ($b:expr) ahead ! [ $($items:tt)* ]) => { $b.positive_lookahead($crate::pattern!($($items)*)) };
+    (@append ($b:expr) not_ahead ! [ $($items:tt)* ]) => { $b.negative_lookahead($crate::pattern!($($items)*)) };
+    (@append ($b:expr) behind ! [ $($items:tt)* ]) => { $b.positive_lookbehind($crate::pattern!($($items)*)) };
+    (@append ($b:expr) not_behind ! [ $($items:tt)* ]) => { $b.negative_lookbehind($crate::pattern!($($items)*)) };
+    (@append ($b:expr) $lo:literal ..= $hi:literal) => { $b.range_character_class($lo, $hi) };
+    (@append ($b:expr) $literal:literal) => { $crate::__private::Literal::append_to($literal, $b) };
+    (@append ($b:expr) $($item:tt)*) => {
+        ::std::compile_error!(::std::concat!("unknown pattern item `", ::std::stringify!($($item)*), "`"))
+    };
+
+    // Rejects the items that the `regex` crate cannot compile, at any depth.
+    (@check) => {};
+    (@check $name:ident ! [ $($items:tt)* ] $($rest:tt)*) => {
+        $crate::__pattern!(@look $name);
+        $crate::__pattern!(@check $($items)*);
+        $crate::__pattern!(@check $($rest)*);
+    };
+    (@check same_as ( $($args:tt)* ) $($rest:tt)*) => {
+        ::std::compile_error!("`same_as` is not supported by `regex!`; use `pattern!` with `to_matcher`")
+    };
+    (@check $other:tt $($rest:tt)*) => { $crate::__pattern!(@check $($rest)*) };
+    (@look ahead) => { $crate::__pattern!(@unsupported ahead) };
+    (@look not_ahead) => { $crate::__pattern!(@unsupported not_ahead) };
+    (@look behind) => { $crate::__pattern!(@unsupported behind) };
+    (@look not_behind) => { $crate::__pattern!(@unsupported not_behind) };
+    (@look $name:ident) => {};
+    (@unsupported $name:ident) => {
+        ::std::compile_error!(::std::concat!(
+            "`", ::std::stringify!($name), "!` is not supported by `regex!`; use `pattern!` with `to_matcher`"
+        ))
+    };
+
+    // Splits the branches of `any_of!` at top-level commas.
+    (@branches [$($done:expr),*] []) => { [$($done),*] };
+    (@branches [$($done:expr),*] [$($branch:tt)+]) => { [$($done,)* $crate::pattern!($($branch)+)] };
+    (@branches [$($done:expr),*] [$($branch:tt)*] , $($rest:tt)*) => {
+        $crate::__pattern!(@branches [$($done,)* $crate::pattern!($($branch)*)] [] $($rest)*)
+    };
+    (@branches [$($done:expr),*] [$($branch:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::__pattern!(@branches [$($done),*] [$($branch)* $next] $($rest)*)
+    };
+}
+
+/// Literals accepted by [`pattern!`], appended with `literal` or `string`.
+pub trait Literal {
+    fn append_to(self, builder: crate::RegexBuilder) -> crate::RegexBuilder;
+}
+
+impl Literal for char {
+    fn append_to(self, builder: crate::RegexBuilder) -> crate::RegexBuilder {
+        builder.literal(self)
+    }
+}
+
+impl Literal for &str {
+    fn append_to(self, builder: crate::RegexBuilder) -> crate::RegexBuilder {
+        builder.string(self)
+    }
+}
@@ -0,0 +1,584 @@
+//! Parser turning pattern text back into an expression tree.
+//!
+//! The parser understands the syntax of the `regex` crate, plus look-around
+//! assertions and numbered backreferences. Constructs the tree has no node
+//! for, such as Unicode classes (`\p{Greek}`) or nested class sets, are kept
+//! as [`Node::Raw`] text, so that they still render unchanged.
+
+use crate::ast::{Anchor, Class, ClassItem, ClassSet, Flag, Flags, GroupKind, LookKind, Node, PerlClass, Repetition};
+use crate::error::ParseError;
+
+/// Parses a whole pattern.
+pub(crate) fn parse(pattern: &str) -> Result<Node, ParseError> {
+    let mut parser = Parser {
+        pattern,
+        pos: 0,
+        ignore_whitespace: false,
+    };
+    let node = parser.alternation()?;
+    match parser.peek() {
+        Some(')') => Err(parser.error(parser.pos..parser.pos + 1, "unopened group")),
+        _ => Ok(node),
+    }
+}
+
+/// Returns the name of each capturing group in `nodes`, or `None` for a numbered
+/// group, in the order of their opening parentheses. Groups written in raw pattern
+/// text count too, when the text parses on its own.
+pub(crate) fn group_names(nodes: &[Node]) -> Vec<Option<String>> {
+    let mut names = Vec::new();
+    for node in nodes {
+        node.walk(&mut |node| match node {
+            Node::Group { kind: GroupKind::Capturing, .. } => names.push(None),
+            Node::Group { kind: GroupKind::Named(name), .. } => names.push(Some(name.clone())),
+            Node::Raw(text) => {
+                if let Some(node) = parse_raw(text) {
+                    names.extend(group_names(std::slice::from_ref(&node)));
+                }
+            }
+            _ => {}
+        });
+    }
+    names
+}
+
+/// Parses raw pattern text into a tree, if it parses on its own into more than raw text.
+pub(crate) fn parse_raw(text: &str) -> Option<Node> {
+    match parse(text) {
+        Ok(Node::Raw(_)) | Err(_) => None,
+        Ok(node) => Some(node),
+    }
+}
+
+struct Parser<'p> {
+    pattern: &'p str,
+    pos: usize,
+    ignore_whitespace: bool,
+}
+
+/// What an opening parenthesis turned out to be.
+enum Group {
+    Node(Node),
+    /// `(?flags)`, which applies to the rest of the enclosing group.
+    Directive(Flags),
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.pattern[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        if self.pattern[self.pos..].starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, span: std::ops::Range<usize>, message: &str) -> ParseError {
+        ParseError::new(self.pattern.to_string(), message.to_string(), span)
+    }
+
+    /// Skips whitespace and `#` comments when the `x` flag is in effect.
+    fn skip_ignored(&mut self) {
+        while self.ignore_whitespace {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => self.pos += c.len_utf8(),
+                Some('#') => {
+                    let rest = &self.pattern[self.pos..];
+                    self.pos += rest.find('\n').map_or(rest.len(), |end| end + 1);
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn alternation(&mut self) -> Result<Node, ParseError> {
+        let mut branches = Vec::new();
+        // Flags set by `(?flags)` carry over into the following branches.
+        let mut carried = Flags::default();
+        loop {
+            let flags = carried.clone();
+            let branch = self.concat(&mut carried)?;
+            branches.push(with_flags(flags, branch));
+            if !self.eat('|') {
+                break;
+            }
+        }
+        Ok(if branches.len() == 1 {
+            branches.pop().unwrap()
+        } else {
+            Node::Alternation(branches)
+        })
+    }
+
+    fn concat(&mut self, carried: &mut Flags) -> Result<Node, ParseError> {
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut quantified = false;
+        loop {
+            self.skip_ignored();
+            let start = self.pos;
+            let node = match self.peek() {
+                None | Some('|') | Some(')') => break,
+                Some('*' | '+' | '?' | '{') => {
+                    let repetition = self.repetition()?;
+                    let greedy = !self.eat('?');
+                    let node = match nodes.pop() {
+                        Some(node) if !quantified => node,
+                        _ => return Err(self.error(start..self.pos, "repetition operator missing expression")),
+                    };
+                    nodes.push(Node::Repeat {
+                        node: Box::new(node),
+                        repetition,
+                        greedy,
+                    });
+                    quantified = true;
+                    continue;
+                }
+                Some('(') => match self.group()? {
+                    Group::Node(node) => node,
+                    Group::Directive(flags) => {
+                        merge(carried, &flags);
+                        let rest = self.concat(carried)?;
+                        nodes.push(with_flags(flags, rest));
+                        break;
+                    }
+                },
+                Some('[') => self.class()?,
+                Some('\\') => self.escape()?,
+                Some(_) => match self.bump().unwrap() {
+                    '.' => Node::Dot,
+                    '^' => Node::Anchor(Anchor::StartOfLine),
+                    '$' => Node::Anchor(Anchor::EndOfLine),
+                    c => Node::Literal(c),
+                },
+            };
+            nodes.push(node);
+            quantified = false;
+        }
+        Ok(Node::concat(merge_literals(nodes)))
+    }
+
+    fn repetition(&mut self) -> Result<Repetition, ParseError> {
+        let start = self.pos;
+        let repetition = match self.bump() {
+            Some('*') => Repetition::ZeroOrMore,
+            Some('+') => Repetition::OneOrMore,
+            Some('?') => Repetition::ZeroOrOne,
+            _ => {
+                let end = match self.pattern[self.pos..].find('}') {
+                    Some(end) => self.pos + end,
+                    None => return Err(self.error(start..self.pattern.len(), "unclosed counted repetition")),
+                };
+                let body: String = self.pattern[self.pos..end].chars().filter(|c| !c.is_whitespace()).collect();
+                self.pos = end + 1;
+                let invalid = || self.error(start..end + 1, "invalid counted repetition");
+                let number = |text: &str| text.parse::<usize>().map_err(|_| invalid());
+                match body.split_once(',') {
+                    None => Repetition::Exactly(number(&body)?),
+                    Some((n, "")) => Repetition::AtLeast(number(n)?),
+                    Some((n, m)) => {
+                        let (n, m) = (number(n)?, number(m)?);
+                        if n > m {
+                            return Err(self.error(start..end + 1, "invalid repetition range"));
+                        }
+                        Repetition::Between(n, m)
+                    }
+                }
+            }
+        };
+        Ok(repetition)
+    }
+
+    fn group(&mut self) -> Result<Group, ParseError> {
+        let start = self.pos;
+        self.bump();
+        let ignore_whitespace = self.ignore_whitespace;
+        let make: Box<dyn FnOnce(Node) -> Node> = if self.eat_str("?:") {
+            Box::new(non_capturing)
+        } else if let Some(kind) = self.look_kind() {
+            Box::new(move |node| Node::Look {
+                kind,
+                node: Box::new(node),
+            })
+        } else if self.eat_str("?P<") || self.eat_str("?<") {
+            let name_start = self.pos;
+            let name_end = self.pattern[self.pos..].find('>').map(|end| self.pos + end);
+            let name = name_end.map(|end| &self.pattern[name_start..end]);
+            let valid = name.is_some_and(|name| {
+                name.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+                    && name.chars().all(|c| c.is_alphanumeric() || "_.[]".contains(c))
+            });
+            match (name, name_end) {
+                (Some(name), Some(end)) if valid => {
+                    self.pos = end + 1;
+                    let kind = GroupKind::Named(name.to_string());
+                    Box::new(move |node| Node::Group {
+                        kind,
+                        node: Box::new(node),
+                    })
+                }
+                _ => return Err(self.error(start..name_end.map_or(self.pattern.len(), |end| end + 1), "invalid capture group name")),
+            }
+        } else if self.eat('?') {
+            let flags = self.flags(start)?;
+            if self.eat(')') {
+                return Ok(Group::Directive(flags));
+            }
+            self.bump();
+            if flags == Flags::default() {
+                Box::new(non_capturing)
+            } else {
+                Box::new(move |node| with_flags(flags, node))
+            }
+        } else {
+            Box::new(|node| Node::Group {
+                kind: GroupKind::Capturing,
+                node: Box::new(node),
+            })
+        };
+        let node = self.alternation()?;
+        self.ignore_whitespace = ignore_whitespace;
+        if !self.eat(')') {
+            return Err(self.error(start..start + 1, "unclosed group"));
+        }
+        Ok(Group::Node(make(node)))
+    }
+
+    fn look_kind(&mut self) -> Option<LookKind> {
+        [
+            ("?=", LookKind::PositiveLookahead),
+            ("?!", LookKind::NegativeLookahead),
+            ("?<=", LookKind::PositiveLookbehind),
+            ("?<!", LookKind::NegativeLookbehind),
+        ]
+        .into_iter()
+        .find(|(prefix, _)| self.eat_str(prefix))
+        .map(|(_, kind)| kind)
+    }
+
+    /// Parses the flags of `(?flags)` or `(?flags:`, leaving the closing
+    /// character unconsumed. The `x` flag only affects how the rest of the
+    /// pattern is parsed, so it is applied here and not kept in the tree.
+    fn flags(&mut self, start: usize) -> Result<Flags, ParseError> {
+        let mut flags = Flags::default();
+        let mut negated = false;
+        loop {
+            let flag = match self.peek() {
+                Some(':' | ')') => break,
+                Some('-') if !negated => {
+                    self.bump();
+                    negated = true;
+                    continue;
+                }
+                Some('i') => Flag::CaseInsensitive,
+                Some('m') => Flag::MultiLine,
+                Some('s') => Flag::DotMatchesNewLine,
+                Some('x') => Flag::IgnoreWhitespace,
+                Some('U') => Flag::SwapGreed,
+                Some('R') => Flag::Crlf,
+                Some(c) => return Err(self.error(self.pos..self.pos + c.len_utf8(), "unrecognized flag")),
+                None => return Err(self.error(start..start + 1, "unclosed group")),
+            };
+            self.bump();
+            if flag == Flag::IgnoreWhitespace {
+                self.ignore_whitespace = !negated;
+            } else if negated {
+                flags.disabled.push(flag);
+            } else {
+                flags.enabled.push(flag);
+            }
+        }
+        if self.pos == start + 2 || self.pattern[..self.pos].ends_with('-') {
+            return Err(self.error(start..self.pos + 1, "invalid flag group"));
+        }
+        Ok(flags)
+    }
+
+    fn escape(&mut self) -> Result<Node, ParseError> {
+        let start = self.pos;
+        self.bump();
+        let Some(c) = self.peek() else {
+            return Err(self.error(start..self.pos, "incomplete escape sequence"));
+        };
+        let node = match c {
+            'b' if self.pattern[self.pos..].starts_with("b{") => return self.raw_until(start, '}'),
+            '<' | '>' => {
+                self.bump();
+                Node::Raw(self.pattern[start..self.pos].to_string())
+            }
+            'p' | 'P' => return self.unicode_class(start),
+            'k' if self.pattern[self.pos..].starts_with("k<") => {
+                let Some(end) = self.pattern[self.pos..].find('>').map(|end| self.pos + end) else {
+                    return Err(self.error(start..self.pattern.len(), "incomplete escape sequence"));
+                };
+                let name = self.pattern[self.pos + 2..end].to_string();
+                self.pos = end + 1;
+                Node::NamedBackreference(name)
+            }
+            'k' => return self.raw_until(start, '>'),
+            '1'..='9' => {
+                let digits = self.pattern[self.pos..].chars().take_while(char::is_ascii_digit).count();
+                self.pos += digits;
+                match self.pattern[start + 1..self.pos].parse() {
+                    Ok(group) => Node::Backreference(group),
+                    Err(_) => return Err(self.error(start..self.pos, "invalid backreference")),
+                }
+            }
+            'b' | 'B' | 'A' | 'z' => {
+                self.bump();
+                Node::Anchor(match c {
+                    'b' => Anchor::WordBoundary,
+                    'B' => Anchor::NonWordBoundary,
+                    'A' => Anchor::StartOfText,
+                    _ => Anchor::EndOfText,
+                })
+            }
+            _ => match self.class_escape(start)? {
+                ClassItem::Perl(class) => Node::Class(Class::Perl(class)),
+                ClassItem::Char(c) => Node::Literal(c),
+                _ => unreachable!("class escapes are characters or Perl classes"),
+            },
+        };
+        Ok(node)
+    }
+
+    /// Parses an escape valid both inside and outside classes, after the backslash.
+    fn class_escape(&mut self, start: usize) -> Result<ClassItem, ParseError> {
+        let c = self.bump().ok_or_else(|| self.error(start..self.pos, "incomplete escape sequence"))?;
+        let item = match c {
+            'd' => ClassItem::Perl(PerlClass::Digit),
+            'D' => ClassItem::Perl(PerlClass::NonDigit),
+            'w' => ClassItem::Perl(PerlClass::Word),
+            'W' => ClassItem::Perl(PerlClass::NonWord),
+            's' => ClassItem::Perl(PerlClass::Whitespace),
+            'S' => ClassItem::Perl(PerlClass::NonWhitespace),
+            'n' => ClassItem::Char('\n'),
+            'r' => ClassItem::Char('\r'),
+            't' => ClassItem::Char('\t'),
+            'f' => ClassItem::Char('\x0C'),
+            'v' => ClassItem::Char('\x0B'),
+            'a' => ClassItem::Char('\x07'),
+            'x' | 'u' | 'U' => ClassItem::Char(self.hex(start, c)?),
+            c if c.is_ascii() && !c.is_ascii_alphanumeric() && c != '<' && c != '>' => ClassItem::Char(c),
+            _ => return Err(self.error(start..self.pos, "unrecognized escape sequence")),
+        };
+        Ok(item)
+    }
+
+    /// Parses the digits of `\x7F`, `\x{10FFFF}`, `\uFFFF` or `\UFFFFFFFF`.
+    fn hex(&mut self, start: usize, kind: char) -> Result<char, ParseError> {
+        let digits = if self.eat('{') {
+            let end = self.pattern[self.pos..].find('}').map(|end| self.pos + end);
+            let Some(end) = end else {
+                return Err(self.error(start..self.pattern.len(), "unclosed hexadecimal escape"));
+            };
+            let digits = &self.pattern[self.pos..end];
+            self.pos = end + 1;
+            digits
+        } else {
+            let len = match kind {
+                'x' => 2,
+                'u' => 4,
+                _ => 8,
+            };
+            let digits = self.pattern[self.pos..].get(..len).unwrap_or("");
+            self.pos += digits.len();
+            digits
+        };
+        u32::from_str_radix(digits, 16)
+            .ok()
+            .filter(|_| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit()))
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.error(start..self.pos, "invalid hexadecimal escape"))
+    }
+
+    /// Keeps `\pL` or `\p{Greek}` as raw text.
+    fn unicode_class(&mut self, start: usize) -> Result<Node, ParseError> {
+        self.bump();
+        if self.peek() == Some('{') {
+            return self.raw_until(start, '}');
+        }
+        match self.bump() {
+            Some(_) => Ok(Node::Raw(self.pattern[start..self.pos].to_string())),
+            None => Err(self.error(start..self.pos, "incomplete escape sequence")),
+        }
+    }
+
+    fn raw_until(&mut self, start: usize, close: char) -> Result<Node, ParseError> {
+        match self.pattern[self.pos..].find(close) {
+            Some(end) => {
+                self.pos += end + close.len_utf8();
+                Ok(Node::Raw(self.pattern[start..self.pos].to_string()))
+            }
+            None => Err(self.error(start..self.pattern.len(), "incomplete escape sequence")),
+        }
+    }
+
+    fn class(&mut self) -> Result<Node, ParseError> {
+        let start = self.pos;
+        self.bump();
+        let negated = self.eat('^');
+        let mut items = Vec::new();
+        loop {
+            if self.ignore_whitespace {
+                while self.peek().is_some_and(char::is_whitespace) {
+                    self.bump();
+                }
+            }
+            let item_start = self.pos;
+            let item = match self.peek() {
+                None => return Err(self.error(start..start + 1, "unclosed character class")),
+                Some(']') if !items.is_empty() => {
+                    self.bump();
+                    break;
+                }
+                Some('[') if self.pattern[self.pos..].starts_with("[:") => match self.ascii_class() {
+                    Some(item) => item,
+                    None => return self.raw_class(start),
+                },
+                Some('[') => return self.raw_class(start),
+                Some('\\') if self.pattern[self.pos..].starts_with("\\p") || self.pattern[self.pos..].starts_with("\\P") => {
+                    return self.raw_class(start)
+                }
+                Some('\\') => {
+                    self.bump();
+                    self.class_escape(item_start)?
+                }
+                Some(_) if ["&&", "--", "~~"].iter().any(|op| self.pattern[self.pos..].starts_with(op)) => {
+                    return self.raw_class(start)
+                }
+                Some(c) => {
+                    self.bump();
+                    ClassItem::Char(c)
+                }
+            };
+            let item = match item {
+                ClassItem::Char(first) if self.peek() == Some('-') && !self.pattern[self.pos + 1..].starts_with(']') => {
+                    self.bump();
+                    let last = match self.bump() {
+                        Some('\\') => self.class_escape(self.pos - 1)?,
+                        Some(c) => ClassItem::Char(c),
+                        None => return Err(self.error(start..start + 1, "unclosed character class")),
+                    };
+                    match last {
+                        ClassItem::Char(last) if first <= last => ClassItem::Range(first, last),
+                        _ => return Err(self.error(item_start..self.pos, "invalid character class range")),
+                    }
+                }
+                item => item,
+            };
+            items.push(item);
+        }
+        Ok(Node::Class(Class::Set(ClassSet { negated, items })))
+    }
+
+    /// Parses `[:alpha:]`, or returns `None` for syntax kept as raw text.
+    fn ascii_class(&mut self) -> Option<ClassItem> {
+        let rest = &self.pattern[self.pos + 2..];
+        let end = rest.find(":]")?;
+        let name = &rest[..end];
+        if !name.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        self.pos += end + 4;
+        Some(ClassItem::Ascii(name.to_string()))
+    }
+
+    /// Keeps a class using syntax the tree has no node for, such as nested
+    /// classes or set operations, as raw text.
+    fn raw_class(&mut self, start: usize) -> Result<Node, ParseError> {
+        self.pos = start;
+        let mut depth = 0;
+        while let Some(c) = self.bump() {
+            match c {
+                '\\' => {
+                    self.bump();
+                }
+                '[' => {
+                    depth += 1;
+                    self.eat('^');
+                    // A `]` right after the opening bracket is literal.
+                    if self.peek() == Some(']') {
+                        self.bump();
+                    }
+                }
+                ']' if depth == 1 => return Ok(Node::Raw(self.pattern[start..self.pos].to_string())),
+                ']' => depth -= 1,
+                _ => {}
+            }
+        }
+        Err(self.error(start..start + 1, "unclosed character class"))
+    }
+}
+
+fn non_capturing(node: Node) -> Node {
+    match node {
+        // An alternation already renders inside a non-capturing group.
+        node @ Node::Alternation(_) => node,
+        node => Node::Group {
+            kind: GroupKind::NonCapturing,
+            node: Box::new(node),
+        },
+    }
+}
+
+fn with_flags(flags: Flags, node: Node) -> Node {
+    if flags == Flags::default() {
+        node
+    } else {
+        Node::Flags {
+            flags,
+            node: Box::new(node),
+        }
+    }
+}
+
+/// Adds the flags set or cleared by `flags` to `carried`.
+fn merge(carried: &mut Flags, flags: &Flags) {
+    for flag in &flags.enabled {
+        carried.disabled.retain(|f| f != flag);
+        if !carried.enabled.contains(flag) {
+            carried.enabled.push(*flag);
+        }
+    }
+    for flag in &flags.disabled {
+        carried.enabled.retain(|f| f != flag);
+        if !carried.disabled.contains(flag) {
+            carried.disabled.push(*flag);
+        }
+    }
+}
+
+/// Joins runs of literal characters into strings, as `string` would append them.
+fn merge_literals(nodes: Vec<Node>) -> Vec<Node> {
+    let mut merged: Vec<Node> = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        match (merged.last_mut(), node) {
+            (Some(Node::Text(text)), Node::Literal(c)) => text.push(c),
+            (Some(last @ Node::Literal(_)), Node::Literal(c)) => {
+                let Node::Literal(first) = *last else { unreachable!() };
+                *last = Node::Text([first, c].iter().collect());
+            }
+            (_, node) => merged.push(node),
+        }
+    }
+    merged
+}
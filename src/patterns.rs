@@ -0,0 +1,500 @@
+//! Builders for common formats, ready to be embedded in bigger patterns.
+//!
+//! Every function returns a fresh [`RegexBuilder`] for the format, without anchors
+//! or capturing groups, so the same builder can be used several times in one
+//! pattern, wrapped in a named group, or anchored to match whole strings.
+//!
+//! The [`phone`] and [`postal`] modules add per-country phone numbers and postal
+//! codes, selectable by ISO country code at runtime.
+//!
+//! # Example
+//!
+//! ```
+//! use simple_regex::{patterns, RegexBuilder};
+//!
+//! let (builder, address) = RegexBuilder::new().start_of_line().named_group("address", patterns::ipv4());
+//! let digit = RegexBuilder::new().range_character_class('0', '9');
+//! let (builder, port) = builder.literal(':').named_group("port", RegexBuilder::new().range_repetitions(digit, 1, 5));
+//! let regex = builder.end_of_line().to_regex().unwrap();
+//! let captures = regex.captures("192.168.0.1:8080").unwrap();
+//! assert_eq!(address.as_str(&captures), Some("192.168.0.1"));
+//! assert_eq!(port.parse::<u16>(&captures).unwrap(), 8080);
+//! assert!(!regex.is_match("192.168.0.256:8080"));
+//! ```
+
+use crate::RegexBuilder;
+
+pub mod phone;
+pub mod postal;
+
+fn new() -> RegexBuilder {
+    RegexBuilder::new()
+}
+
+/// A digit from 0 to 9. `RegexBuilder::digit` also matches the digits of other scripts.
+fn ascii_digit() -> RegexBuilder {
+    new().range_character_class('0', '9')
+}
+
+fn hex_digit() -> RegexBuilder {
+    new().character_class("0-9A-Fa-f")
+}
+
+fn hex_digits(count: usize) -> RegexBuilder {
+    new().exact_repetitions(hex_digit(), count)
+}
+
+/// `0` or a number without leading zeros.
+fn numeric_identifier() -> RegexBuilder {
+    new().any_of([
+        new().literal('0'),
+        new().range_character_class('1', '9').zero_or_more(ascii_digit()),
+    ])
+}
+
+/// Matches an email address: the dot-atom form of RFC 5322 for the local part, and a
+/// domain name ending in an alphabetic top-level domain.
+///
+/// Quoted local parts, comments and IP address literals are not matched.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{patterns, RegexBuilder};
+///
+/// let regex = RegexBuilder::new().start_of_line().group(patterns::email()).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("first.last+tag@mail.example.com"));
+/// assert!(regex.is_match("o'brien@example.ie"));
+/// assert!(!regex.is_match("first..last@example.com"));
+/// assert!(!regex.is_match("user@-example.com"));
+/// assert!(!regex.is_match("user@localhost"));
+/// ```
+pub fn email() -> RegexBuilder {
+    let atom = || new().one_or_more(new().character_class("A-Za-z0-9!#$%&'*+/=?^_`{|}~-"));
+    new()
+        .then(atom())
+        .zero_or_more(new().literal('.').then(atom()))
+        .literal('@')
+        .one_or_more(domain_label().literal('.'))
+        .range_repetitions(new().character_class("A-Za-z"), 2, 63)
+}
+
+/// A domain name label: letters, digits and inner hyphens, at most 63 characters.
+fn domain_label() -> RegexBuilder {
+    let alphanumeric = || new().character_class("A-Za-z0-9");
+    new()
+        .then(alphanumeric())
+        .zero_or_one(new().range_repetitions(new().character_class("A-Za-z0-9-"), 0, 61).then(alphanumeric()))
+}
+
+/// Matches an absolute URL with a scheme, a host name, an IPv4 address or a bracketed
+/// IPv6 address, and an optional port, path, query and fragment.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{patterns, RegexBuilder};
+///
+/// let regex = RegexBuilder::new().start_of_line().group(patterns::url()).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("https://example.com"));
+/// assert!(regex.is_match("http://localhost:8080/api/v1?page=2#top"));
+/// assert!(regex.is_match("ftp://[2001:db8::1]/file.txt"));
+/// assert!(!regex.is_match("example.com/path"));
+/// assert!(!regex.is_match("https://exa mple.com"));
+/// ```
+pub fn url() -> RegexBuilder {
+    let scheme = new()
+        .character_class("A-Za-z")
+        .zero_or_more(new().character_class("A-Za-z0-9+.-"));
+    let host = new().any_of([
+        new().literal('[').then(ipv6()).literal(']'),
+        ipv4(),
+        domain_label().zero_or_more(new().literal('.').then(domain_label())),
+    ]);
+    new()
+        .then(scheme)
+        .string("://")
+        .then(host)
+        .zero_or_one(new().literal(':').range_repetitions(ascii_digit(), 1, 5))
+        .zero_or_one(new().literal('/').zero_or_more(new().negated_character_class("\\s?#")))
+        .zero_or_one(new().literal('?').zero_or_more(new().negated_character_class("\\s#")))
+        .zero_or_one(new().literal('#').zero_or_more(new().non_whitespace()))
+}
+
+/// Matches an IPv4 address in dotted-decimal notation, each part from 0 to 255
+/// without leading zeros.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{patterns, RegexBuilder};
+///
+/// let regex = RegexBuilder::new().start_of_line().group(patterns::ipv4()).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("127.0.0.1"));
+/// assert!(regex.is_match("255.255.255.255"));
+/// assert!(!regex.is_match("256.0.0.1"));
+/// assert!(!regex.is_match("1.2.3"));
+/// assert!(!regex.is_match("01.2.3.4"));
+/// assert!(!regex.is_match("١٢٧.0.0.1"));
+/// ```
+pub fn ipv4() -> RegexBuilder {
+    let octet = || {
+        new().any_of([
+            new().string("25").range_character_class('0', '5'),
+            new().literal('2').range_character_class('0', '4').range_character_class('0', '9'),
+            new().literal('1').exact_repetitions(ascii_digit(), 2),
+            new().zero_or_one(new().range_character_class('1', '9')).range_character_class('0', '9'),
+        ])
+    };
+    octet().exact_repetitions(new().literal('.').then(octet()), 3)
+}
+
+/// Matches an IPv6 address in any of the forms of RFC 4291: full, compressed with
+/// `::`, or ending in an embedded IPv4 address.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{patterns, RegexBuilder};
+///
+/// let regex = RegexBuilder::new().start_of_line().group(patterns::ipv6()).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("2001:0db8:85a3:0000:0000:8a2e:0370:7334"));
+/// assert!(regex.is_match("2001:db8::8a2e:370:7334"));
+/// assert!(regex.is_match("::1"));
+/// assert!(regex.is_match("::"));
+/// assert!(regex.is_match("::ffff:192.0.2.128"));
+/// assert!(!regex.is_match("2001:db8::85a3::7334"));
+/// assert!(!regex.is_match("12345::"));
+/// ```
+pub fn ipv6() -> RegexBuilder {
+    let h16 = || new().range_repetitions(hex_digit(), 1, 4);
+    // `n` groups, each followed by a colon.
+    let groups = |n: usize| new().exact_repetitions(h16().literal(':'), n);
+    // Up to `n + 1` groups separated by colons, before a `::`.
+    let head = |n: usize| new().zero_or_one(new().range_repetitions(h16().literal(':'), 0, n).then(h16()));
+    // The last 32 bits, as an IPv4 address or two groups.
+    let low = || new().any_of([ipv4(), h16().literal(':').then(h16())]);
+    new().any_of([
+        groups(6).then(low()),
+        new().string("::").then(groups(5)).then(low()),
+        new().zero_or_one(h16()).string("::").then(groups(4)).then(low()),
+        head(1).string("::").then(groups(3)).then(low()),
+        head(2).string("::").then(groups(2)).then(low()),
+        head(3).string("::").then(groups(1)).then(low()),
+        head(4).string("::").then(low()),
+        head(5).string("::").then(h16()),
+        head(6).string("::"),
+    ])
+}
+
+/// Matches an IPv4 or IPv6 address followed by a prefix length, such as
+/// `10.0.0.0/8` or `2001:db8::/32`.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{patterns, RegexBuilder};
+///
+/// let regex = RegexBuilder::new().start_of_line().group(patterns::cidr()).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("192.168.0.0/16"));
+/// assert!(regex.is_match("2001:db8::/128"));
+/// assert!(!regex.is_match("192.168.0.0/33"));
+/// assert!(!regex.is_match("2001:db8::/129"));
+/// assert!(!regex.is_match("192.168.0.0"));
+/// ```
+pub fn cidr() -> RegexBuilder {
+    let ipv4_prefix = new().any_of([
+        new().literal('3').range_character_class('0', '2'),
+        new().zero_or_one(new().character_class("12")).range_character_class('0', '9'),
+    ]);
+    let ipv6_prefix = new().any_of([
+        new().string("12").range_character_class('0', '8'),
+        new().literal('1').character_class("01").range_character_class('0', '9'),
+        new().zero_or_one(new().range_character_class('1', '9')).range_character_class('0', '9'),
+    ]);
+    new().any_of([
+        ipv4().literal('/').then(ipv4_prefix),
+        ipv6().literal('/').then(ipv6_prefix),
+    ])
+}
+
+/// Matches a MAC address as six pairs of hexadecimal digits, separated throughout
+/// by either colons or hyphens.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{patterns, RegexBuilder};
+///
+/// let regex = RegexBuilder::new().start_of_line().group(patterns::mac()).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("00:1A:2b:3C:4d:5E"));
+/// assert!(regex.is_match("00-1A-2B-3C-4D-5E"));
+/// assert!(!regex.is_match("00:1A-2B:3C:4D:5E"));
+/// assert!(!regex.is_match("00:1A:2B:3C:4D"));
+/// ```
+pub fn mac() -> RegexBuilder {
+    let separated = |separator: char| hex_digits(2).exact_repetitions(new().literal(separator).then(hex_digits(2)), 5);
+    new().any_of([separated(':'), separated('-')])
+}
+
+/// Matches a UUID in its canonical hyphenated form, in either case.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{patterns, RegexBuilder};
+///
+/// let regex = RegexBuilder::new().start_of_line().group(patterns::uuid()).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("123e4567-e89b-12d3-a456-426614174000"));
+/// assert!(regex.is_match("123E4567-E89B-12D3-A456-426614174000"));
+/// assert!(!regex.is_match("123e4567e89b12d3a456426614174000"));
+/// assert!(!regex.is_match("123e4567-e89b-12d3-a456-42661417400g"));
+/// ```
+pub fn uuid() -> RegexBuilder {
+    hex_digits(8)
+        .literal('-')
+        .then(hex_digits(4))
+        .literal('-')
+        .then(hex_digits(4))
+        .literal('-')
+        .then(hex_digits(4))
+        .literal('-')
+        .then(hex_digits(12))
+}
+
+/// Matches an ISO 8601 calendar date, `YYYY-MM-DD`, with months from 01 to 12 and
+/// days from 01 to 31.
+///
+/// The number of days in the month is not checked, so `2023-02-31` matches.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{patterns, RegexBuilder};
+///
+/// let regex = RegexBuilder::new().start_of_line().group(patterns::iso_date()).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("2024-02-29"));
+/// assert!(!regex.is_match("2024-13-01"));
+/// assert!(!regex.is_match("2024-00-10"));
+/// assert!(!regex.is_match("2024-1-1"));
+/// assert!(!regex.is_match("٢٠٢٤-01-01"));
+/// ```
+pub fn iso_date() -> RegexBuilder {
+    let month = new().any_of([
+        new().literal('0').range_character_class('1', '9'),
+        new().literal('1').range_character_class('0', '2'),
+    ]);
+    let day = new().any_of([
+        new().literal('0').range_character_class('1', '9'),
+        new().character_class("12").range_character_class('0', '9'),
+        new().literal('3').character_class("01"),
+    ]);
+    new()
+        .exact_repetitions(ascii_digit(), 4)
+        .literal('-')
+        .then(month)
+        .literal('-')
+        .then(day)
+}
+
+/// Matches an ISO 8601 time of day, `hh:mm`, with optional seconds and fraction of a
+/// second.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{patterns, RegexBuilder};
+///
+/// let regex = RegexBuilder::new().start_of_line().group(patterns::iso_time()).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("23:59"));
+/// assert!(regex.is_match("08:30:15.250"));
+/// assert!(!regex.is_match("24:00"));
+/// assert!(!regex.is_match("12:60"));
+/// ```
+pub fn iso_time() -> RegexBuilder {
+    let sixty = || new().range_character_class('0', '5').range_character_class('0', '9');
+    let hour = new().any_of([
+        new().character_class("01").range_character_class('0', '9'),
+        new().literal('2').range_character_class('0', '3'),
+    ]);
+    hour.literal(':').then(sixty()).zero_or_one(
+        new()
+            .literal(':')
+            .then(sixty())
+            .zero_or_one(new().literal('.').one_or_more(ascii_digit())),
+    )
+}
+
+/// Matches an ISO 8601 date and time, `YYYY-MM-DDThh:mm`, with the time as in
+/// [`iso_time`] and an optional `Z` or `±hh:mm` offset.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{patterns, RegexBuilder};
+///
+/// let regex = RegexBuilder::new().start_of_line().group(patterns::iso_datetime()).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("2024-05-17T08:30:00Z"));
+/// assert!(regex.is_match("2024-05-17T08:30:00.5+02:00"));
+/// assert!(regex.is_match("2024-05-17T08:30"));
+/// assert!(!regex.is_match("2024-05-17 08:30"));
+/// ```
+pub fn iso_datetime() -> RegexBuilder {
+    let offset = new().any_of([
+        new().literal('Z'),
+        new()
+            .character_class("+-")
+            .exact_repetitions(ascii_digit(), 2)
+            .literal(':')
+            .exact_repetitions(ascii_digit(), 2),
+    ]);
+    iso_date().literal('T').then(iso_time()).zero_or_one(offset)
+}
+
+/// Matches a semantic version as defined by Semantic Versioning 2.0.0, with optional
+/// pre-release and build metadata.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{patterns, RegexBuilder};
+///
+/// let regex = RegexBuilder::new().start_of_line().group(patterns::semver()).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("1.0.0"));
+/// assert!(regex.is_match("2.1.3-alpha.1+build.5"));
+/// assert!(!regex.is_match("1.0"));
+/// assert!(!regex.is_match("01.0.0"));
+/// assert!(!regex.is_match("1.0.0-01"));
+/// ```
+pub fn semver() -> RegexBuilder {
+    let alphanumeric = || new().character_class("0-9A-Za-z-");
+    let pre_release_identifier = || {
+        new().any_of([
+            numeric_identifier(),
+            new().zero_or_more(ascii_digit()).character_class("A-Za-z-").zero_or_more(alphanumeric()),
+        ])
+    };
+    let build_identifier = || new().one_or_more(alphanumeric());
+    numeric_identifier()
+        .literal('.')
+        .then(numeric_identifier())
+        .literal('.')
+        .then(numeric_identifier())
+        .zero_or_one(
+            new()
+                .literal('-')
+                .then(pre_release_identifier())
+                .zero_or_more(new().literal('.').then(pre_release_identifier())),
+        )
+        .zero_or_one(
+            new()
+                .literal('+')
+                .then(build_identifier())
+                .zero_or_more(new().literal('.').then(build_identifier())),
+        )
+}
+
+/// Matches a CSS hexadecimal color: `#` followed by 3, 4, 6 or 8 hexadecimal digits.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{patterns, RegexBuilder};
+///
+/// let regex = RegexBuilder::new().start_of_line().group(patterns::hex_color()).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("#fff"));
+/// assert!(regex.is_match("#1E90FF"));
+/// assert!(regex.is_match("#1e90ff80"));
+/// assert!(!regex.is_match("#12345"));
+/// assert!(!regex.is_match("1e90ff"));
+/// ```
+pub fn hex_color() -> RegexBuilder {
+    new()
+        .literal('#')
+        .any_of([hex_digits(8), hex_digits(6), hex_digits(4), hex_digits(3)])
+}
+
+/// Matches a payment card number of 13 to 19 digits, optionally separated by single
+/// spaces or hyphens.
+///
+/// The pattern only checks the shape of the number; use [`luhn`] to check its
+/// check digit.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{patterns, RegexBuilder};
+///
+/// let regex = RegexBuilder::new().start_of_line().group(patterns::credit_card()).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("4111 1111 1111 1111"));
+/// assert!(regex.is_match("4111-1111-1111-1111"));
+/// assert!(regex.is_match("378282246310005"));
+/// assert!(!regex.is_match("4111 1111"));
+/// assert!(!regex.is_match("4111  1111 1111 1111"));
+/// assert!(!regex.is_match("٤١١١ ١١١١ ١١١١ ١١١١"));
+/// ```
+pub fn credit_card() -> RegexBuilder {
+    new()
+        .range_character_class('0', '9')
+        .range_repetitions(
+            new().zero_or_one(new().character_class(" -")).range_character_class('0', '9'),
+            12,
+            18,
+        )
+}
+
+/// Returns `true` if the digits of `number` pass the Luhn checksum used by payment
+/// card numbers. Spaces and hyphens are ignored; any other non-digit character, or
+/// a number with fewer than 13 or more than 19 digits (the lengths [`credit_card`]
+/// matches), fails the check.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns;
+///
+/// assert!(patterns::luhn("4111 1111 1111 1111"));
+/// assert!(patterns::luhn("378282246310005"));
+/// assert!(!patterns::luhn("4111 1111 1111 1112"));
+/// assert!(!patterns::luhn("4111-1111-1111-111x"));
+/// assert!(!patterns::luhn("0"));
+/// ```
+pub fn luhn(number: &str) -> bool {
+    let mut sum = 0;
+    let mut digits = 0;
+    for c in number.chars().rev().filter(|c| !matches!(c, ' ' | '-')) {
+        let Some(digit) = c.to_digit(10) else {
+            return false;
+        };
+        sum += match digits % 2 {
+            0 => digit,
+            _ if digit * 2 > 9 => digit * 2 - 9,
+            _ => digit * 2,
+        };
+        digits += 1;
+    }
+    (13..=19).contains(&digits) && sum % 10 == 0
+}
+
+/// Matches a phone number in E.164 format: `+`, a country code that does not start
+/// with 0, and at most 15 digits in total.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::{patterns, RegexBuilder};
+///
+/// let regex = RegexBuilder::new().start_of_line().group(patterns::e164()).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("+14155552671"));
+/// assert!(regex.is_match("+442071838750"));
+/// assert!(!regex.is_match("14155552671"));
+/// assert!(!regex.is_match("+04155552671"));
+/// assert!(!regex.is_match("+1415555267123456"));
+/// assert!(!regex.is_match("+1٤١٥٥٥٥٢٦٧١"));
+/// ```
+pub fn e164() -> RegexBuilder {
+    new()
+        .literal('+')
+        .range_character_class('1', '9')
+        .range_repetitions(ascii_digit(), 1, 14)
+}
@@ -0,0 +1,264 @@
+//! Builders for national phone numbers, written the way they are usually
+//! formatted in each country.
+//!
+//! Each builder accepts the national form, starting with the trunk prefix where
+//! the country has one, and the international form starting with `+` and the
+//! country code. Digits may be grouped with the separators customary in the
+//! country. [`for_country`] selects a builder by ISO 3166-1 alpha-2 code.
+
+use crate::RegexBuilder;
+
+/// The ISO 3166-1 alpha-2 codes of the countries that have a builder.
+pub const COUNTRIES: [&str; 7] = ["AU", "CA", "DE", "FR", "GB", "JP", "US"];
+
+/// Returns the builder for the country with the given ISO 3166-1 alpha-2 code, in
+/// either case, or `None` if there is none.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::phone;
+/// use simple_regex::RegexBuilder;
+///
+/// for code in phone::COUNTRIES {
+///     assert!(phone::for_country(code).is_some());
+/// }
+/// let builder = phone::for_country("fr").unwrap();
+/// let regex = RegexBuilder::new().start_of_line().then(builder).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("01 23 45 67 89"));
+/// assert!(phone::for_country("XX").is_none());
+/// ```
+pub fn for_country(code: &str) -> Option<RegexBuilder> {
+    let builder = match code.to_ascii_uppercase().as_str() {
+        "AU" => au(),
+        "CA" => ca(),
+        "DE" => de(),
+        "FR" => fr(),
+        "GB" => gb(),
+        "JP" => jp(),
+        "US" => us(),
+        _ => return None,
+    };
+    Some(builder)
+}
+
+fn new() -> RegexBuilder {
+    RegexBuilder::new()
+}
+
+fn digits(count: usize) -> RegexBuilder {
+    new().exact_repetitions(new().range_character_class('0', '9'), count)
+}
+
+/// The international prefix `+<code>`, optionally followed by a space, or the
+/// national trunk prefix `0`.
+fn prefix(country_code: &str) -> RegexBuilder {
+    new().any_of([
+        new().literal('+').string(country_code).zero_or_one(new().literal(' ')),
+        new().literal('0'),
+    ])
+}
+
+/// Matches a number of the North American Numbering Plan, as used in the United
+/// States: an optional `+1` or `1`, then a three-digit area code, optionally in
+/// parentheses, and a seven-digit number. Area codes and exchanges do not start
+/// with 0 or 1, and groups may be separated by hyphens, dots or spaces.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::phone;
+/// use simple_regex::RegexBuilder;
+///
+/// let regex = RegexBuilder::new().start_of_line().then(phone::us()).end_of_line().to_regex().unwrap();
+/// for valid in ["(415) 555-2671", "415-555-2671", "415.555.2671", "4155552671", "+1 415 555 2671", "1-415-555-2671"] {
+///     assert!(regex.is_match(valid), "{}", valid);
+/// }
+/// for invalid in ["015-555-2671", "415-055-2671", "555-2671", "415-555-26710", "+44 20 7183 8750", "(٤١٥) ٥٥٥-٢٦٧١"] {
+///     assert!(!regex.is_match(invalid), "{}", invalid);
+/// }
+/// ```
+pub fn us() -> RegexBuilder {
+    let separator = || new().zero_or_one(new().character_class("-. "));
+    let leading = || new().range_character_class('2', '9').then(digits(2));
+    new()
+        .zero_or_one(new().zero_or_one(new().literal('+')).literal('1').then(separator()))
+        .any_of([new().literal('(').then(leading()).literal(')'), leading()])
+        .then(separator())
+        .then(leading())
+        .then(separator())
+        .then(digits(4))
+}
+
+/// Matches a Canadian number, which follows the North American Numbering Plan
+/// like [`us`].
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::phone;
+/// use simple_regex::RegexBuilder;
+///
+/// let regex = RegexBuilder::new().start_of_line().then(phone::ca()).end_of_line().to_regex().unwrap();
+/// for valid in ["(604) 555-0199", "+1 613 555 0142", "416.555.0123"] {
+///     assert!(regex.is_match(valid), "{}", valid);
+/// }
+/// for invalid in ["(104) 555-0199", "604-555-019", "+33 1 23 45 67 89", "(٦٠٤) ٥٥٥-٠١٩٩"] {
+///     assert!(!regex.is_match(invalid), "{}", invalid);
+/// }
+/// ```
+pub fn ca() -> RegexBuilder {
+    us()
+}
+
+/// Matches a number of the United Kingdom: `+44` or the trunk prefix `0`, then ten
+/// digits, or nine for some areas, not starting with 0. The international form may
+/// keep the trunk prefix as `(0)`, and digits may be separated by single spaces.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::phone;
+/// use simple_regex::RegexBuilder;
+///
+/// let regex = RegexBuilder::new().start_of_line().then(phone::gb()).end_of_line().to_regex().unwrap();
+/// for valid in ["020 7183 8750", "+44 20 7183 8750", "+44 (0)20 7183 8750", "07700 900123", "01632 960001", "+447700900123"] {
+///     assert!(regex.is_match(valid), "{}", valid);
+/// }
+/// for invalid in ["7183 8750", "+44 020 7183 8750", "00 7183 8750", "020 7183 87", "020  7183 8750", "٠٢٠ ٧١٨٣ ٨٧٥٠"] {
+///     assert!(!regex.is_match(invalid), "{}", invalid);
+/// }
+/// ```
+pub fn gb() -> RegexBuilder {
+    new()
+        .any_of([
+            new()
+                .string("+44")
+                .zero_or_one(new().literal(' '))
+                .zero_or_one(new().string("(0)").zero_or_one(new().literal(' '))),
+            new().literal('0'),
+        ])
+        .range_character_class('1', '9')
+        .range_repetitions(new().zero_or_one(new().literal(' ')).range_character_class('0', '9'), 8, 9)
+}
+
+/// Matches a German number: `+49` or the trunk prefix `0`, then an area code not
+/// starting with 0 and a subscriber number, 6 to 13 digits in all. The
+/// international form may keep the trunk prefix as `(0)`, and digits may be
+/// separated by spaces, slashes or hyphens.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::phone;
+/// use simple_regex::RegexBuilder;
+///
+/// let regex = RegexBuilder::new().start_of_line().then(phone::de()).end_of_line().to_regex().unwrap();
+/// for valid in ["030 123456", "+49 30 1234567", "+49 (0)89 1234-5678", "089/1234567", "0171 1234567", "+491711234567"] {
+///     assert!(regex.is_match(valid), "{}", valid);
+/// }
+/// for invalid in ["30 123456", "+49 030 123456", "0301", "030 12345678901234", "٠٣٠ ١٢٣٤٥٦"] {
+///     assert!(!regex.is_match(invalid), "{}", invalid);
+/// }
+/// ```
+pub fn de() -> RegexBuilder {
+    new()
+        .any_of([
+            new()
+                .string("+49")
+                .zero_or_one(new().literal(' '))
+                .zero_or_one(new().string("(0)").zero_or_one(new().literal(' '))),
+            new().literal('0'),
+        ])
+        .range_character_class('1', '9')
+        .range_repetitions(new().zero_or_one(new().character_class(" /-")).range_character_class('0', '9'), 5, 12)
+}
+
+/// Matches a French number: `+33` or the trunk prefix `0`, a digit from 1 to 9, and
+/// four pairs of digits, optionally separated by spaces, dots or hyphens.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::phone;
+/// use simple_regex::RegexBuilder;
+///
+/// let regex = RegexBuilder::new().start_of_line().then(phone::fr()).end_of_line().to_regex().unwrap();
+/// for valid in ["01 23 45 67 89", "+33 1 23 45 67 89", "06.12.34.56.78", "0612345678", "+33612345678"] {
+///     assert!(regex.is_match(valid), "{}", valid);
+/// }
+/// for invalid in ["1 23 45 67 89", "00 12 34 56 78", "01 23 45 67", "+33 01 23 45 67 89", "٠١ ٢٣ ٤٥ ٦٧ ٨٩"] {
+///     assert!(!regex.is_match(invalid), "{}", invalid);
+/// }
+/// ```
+pub fn fr() -> RegexBuilder {
+    prefix("33")
+        .range_character_class('1', '9')
+        .exact_repetitions(new().zero_or_one(new().character_class(" .-")).then(digits(2)), 4)
+}
+
+/// Matches a Japanese number: `+81` or the trunk prefix `0`, then either a mobile
+/// number (`70`, `80` or `90`, then two groups of four digits) or a landline number
+/// of nine digits, split into an area code, an exchange and a four-digit line.
+/// Groups may be separated by hyphens or spaces.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::phone;
+/// use simple_regex::RegexBuilder;
+///
+/// let regex = RegexBuilder::new().start_of_line().then(phone::jp()).end_of_line().to_regex().unwrap();
+/// for valid in ["03-1234-5678", "06 6123 4567", "0742-12-3456", "090-1234-5678", "+81 90-1234-5678", "+81 3-1234-5678"] {
+///     assert!(regex.is_match(valid), "{}", valid);
+/// }
+/// for invalid in ["3-1234-5678", "03-1234-567", "090-1234-567", "+81 03-1234-5678", "٠٣-١٢٣٤-٥٦٧٨"] {
+///     assert!(!regex.is_match(invalid), "{}", invalid);
+/// }
+/// ```
+pub fn jp() -> RegexBuilder {
+    let separator = || new().zero_or_one(new().character_class(" -"));
+    let mobile = new()
+        .character_class("789")
+        .literal('0')
+        .then(separator())
+        .then(digits(4))
+        .then(separator())
+        .then(digits(4));
+    // The area code and the exchange share five digits after the first one.
+    let landline = new()
+        .range_character_class('1', '9')
+        .any_of([
+            separator().then(digits(4)),
+            digits(1).then(separator()).then(digits(3)),
+            digits(2).then(separator()).then(digits(2)),
+            digits(3).then(separator()).then(digits(1)),
+        ])
+        .then(separator())
+        .then(digits(4));
+    prefix("81").any_of([mobile, landline])
+}
+
+/// Matches an Australian number: `+61` or the trunk prefix `0`, an area code of 2,
+/// 3, 7 or 8 for landlines or 4 for mobiles, and eight digits, optionally separated
+/// by spaces or hyphens.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::phone;
+/// use simple_regex::RegexBuilder;
+///
+/// let regex = RegexBuilder::new().start_of_line().then(phone::au()).end_of_line().to_regex().unwrap();
+/// for valid in ["02 9876 5432", "+61 2 9876 5432", "0412 345 678", "+61 412 345 678", "0398765432"] {
+///     assert!(regex.is_match(valid), "{}", valid);
+/// }
+/// for invalid in ["01 9876 5432", "02 9876 543", "2 9876 5432", "+61 02 9876 5432", "٠٢ ٩٨٧٦ ٥٤٣٢"] {
+///     assert!(!regex.is_match(invalid), "{}", invalid);
+/// }
+/// ```
+pub fn au() -> RegexBuilder {
+    prefix("61")
+        .character_class("2-478")
+        .exact_repetitions(new().zero_or_one(new().character_class(" -")).range_character_class('0', '9'), 8)
+}
@@ -0,0 +1,250 @@
+//! Builders for national postal codes.
+//!
+//! Letters are matched in upper case, as postal codes are printed; wrap a builder
+//! in `case_insensitive` to accept lower case as well. [`for_country`] selects a
+//! builder by ISO 3166-1 alpha-2 code.
+
+use crate::RegexBuilder;
+
+/// The ISO 3166-1 alpha-2 codes of the countries that have a builder.
+pub const COUNTRIES: [&str; 8] = ["AU", "CA", "DE", "FR", "GB", "JP", "NL", "US"];
+
+/// Returns the builder for the country with the given ISO 3166-1 alpha-2 code, in
+/// either case, or `None` if there is none.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::postal;
+/// use simple_regex::RegexBuilder;
+///
+/// for code in postal::COUNTRIES {
+///     assert!(postal::for_country(code).is_some());
+/// }
+/// let builder = postal::for_country("nl").unwrap();
+/// let regex = RegexBuilder::new().start_of_line().then(builder).end_of_line().to_regex().unwrap();
+/// assert!(regex.is_match("1012 AB"));
+/// assert!(postal::for_country("XX").is_none());
+/// ```
+pub fn for_country(code: &str) -> Option<RegexBuilder> {
+    let builder = match code.to_ascii_uppercase().as_str() {
+        "AU" => au(),
+        "CA" => ca(),
+        "DE" => de(),
+        "FR" => fr(),
+        "GB" => gb(),
+        "JP" => jp(),
+        "NL" => nl(),
+        "US" => us(),
+        _ => return None,
+    };
+    Some(builder)
+}
+
+fn new() -> RegexBuilder {
+    RegexBuilder::new()
+}
+
+fn digits(count: usize) -> RegexBuilder {
+    new().exact_repetitions(new().range_character_class('0', '9'), count)
+}
+
+/// Matches a United States ZIP code, with an optional ZIP+4 extension.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::postal;
+/// use simple_regex::RegexBuilder;
+///
+/// let regex = RegexBuilder::new().start_of_line().then(postal::us()).end_of_line().to_regex().unwrap();
+/// for valid in ["90210", "10001-1234"] {
+///     assert!(regex.is_match(valid), "{}", valid);
+/// }
+/// for invalid in ["9021", "902101", "10001-123", "10001 1234", "٩٠٢١٠"] {
+///     assert!(!regex.is_match(invalid), "{}", invalid);
+/// }
+/// ```
+pub fn us() -> RegexBuilder {
+    digits(5).zero_or_one(new().literal('-').then(digits(4)))
+}
+
+/// Matches a Canadian postal code, `A1A 1A1`, without the letters D, F, I, O, Q and
+/// U, and without W and Z in first position. The space is optional.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::postal;
+/// use simple_regex::RegexBuilder;
+///
+/// let regex = RegexBuilder::new().start_of_line().then(postal::ca()).end_of_line().to_regex().unwrap();
+/// for valid in ["K1A 0B1", "M5V 3L9", "H2X1Y4"] {
+///     assert!(regex.is_match(valid), "{}", valid);
+/// }
+/// for invalid in ["D1A 0B1", "W1A 0B1", "K1A 0D1", "K1A0B", "12345", "K١A ٠B١"] {
+///     assert!(!regex.is_match(invalid), "{}", invalid);
+/// }
+/// ```
+pub fn ca() -> RegexBuilder {
+    let letter = || new().character_class("ABCEGHJ-NPRSTV-Z");
+    new()
+        .character_class("ABCEGHJ-NPRSTVXY")
+        .range_character_class('0', '9')
+        .then(letter())
+        .zero_or_one(new().literal(' '))
+        .range_character_class('0', '9')
+        .then(letter())
+        .range_character_class('0', '9')
+}
+
+/// Matches a postcode of the United Kingdom: an outward code of one or two letters,
+/// a digit, and an optional digit or letter, then an optional space and an inward
+/// code of a digit and two letters. The letters follow the restrictions of each
+/// position, and the special `GIR 0AA` is included.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::postal;
+/// use simple_regex::RegexBuilder;
+///
+/// let regex = RegexBuilder::new().start_of_line().then(postal::gb()).end_of_line().to_regex().unwrap();
+/// for valid in ["SW1A 1AA", "M1 1AE", "B33 8TH", "CR2 6XH", "DN55 1PT", "W1A 0AX", "EC1A1BB", "GIR 0AA"] {
+///     assert!(regex.is_match(valid), "{}", valid);
+/// }
+/// for invalid in ["Q1A 1AA", "SW1A 1AC", "SW1A", "1AA 1AA", "SW1A  1AA", "SW١A ١AA"] {
+///     assert!(!regex.is_match(invalid), "{}", invalid);
+/// }
+/// ```
+pub fn gb() -> RegexBuilder {
+    let first = || new().character_class("A-PR-UWYZ");
+    let second = || new().character_class("A-HK-Y");
+    let outward = new().any_of([
+        first().range_character_class('0', '9').character_class("A-HJKPSTUW"),
+        first().then(second()).range_character_class('0', '9').character_class("ABEHMNPRVWXY"),
+        first().range_repetitions(new().range_character_class('0', '9'), 1, 2),
+        first().then(second()).range_repetitions(new().range_character_class('0', '9'), 1, 2),
+    ]);
+    let inward = new().range_character_class('0', '9').exact_repetitions(new().character_class("ABD-HJLNP-UW-Z"), 2);
+    new().any_of([
+        new().string("GIR 0AA"),
+        outward.zero_or_one(new().literal(' ')).then(inward),
+    ])
+}
+
+/// Matches a German postal code of five digits.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::postal;
+/// use simple_regex::RegexBuilder;
+///
+/// let regex = RegexBuilder::new().start_of_line().then(postal::de()).end_of_line().to_regex().unwrap();
+/// for valid in ["10115", "01067", "80331"] {
+///     assert!(regex.is_match(valid), "{}", valid);
+/// }
+/// for invalid in ["1011", "101155", "D-10115", "١٠١١٥"] {
+///     assert!(!regex.is_match(invalid), "{}", invalid);
+/// }
+/// ```
+pub fn de() -> RegexBuilder {
+    digits(5)
+}
+
+/// Matches a French postal code of five digits, starting with the number of a
+/// department from 01 to 98.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::postal;
+/// use simple_regex::RegexBuilder;
+///
+/// let regex = RegexBuilder::new().start_of_line().then(postal::fr()).end_of_line().to_regex().unwrap();
+/// for valid in ["75008", "01000", "97400", "20000"] {
+///     assert!(regex.is_match(valid), "{}", valid);
+/// }
+/// for invalid in ["00100", "99000", "7500", "750080", "٧٥٠٠٨"] {
+///     assert!(!regex.is_match(invalid), "{}", invalid);
+/// }
+/// ```
+pub fn fr() -> RegexBuilder {
+    new()
+        .any_of([
+            new().literal('0').range_character_class('1', '9'),
+            new().range_character_class('1', '8').range_character_class('0', '9'),
+            new().literal('9').range_character_class('0', '8'),
+        ])
+        .then(digits(3))
+}
+
+/// Matches a Japanese postal code, `123-4567`, with an optional hyphen.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::postal;
+/// use simple_regex::RegexBuilder;
+///
+/// let regex = RegexBuilder::new().start_of_line().then(postal::jp()).end_of_line().to_regex().unwrap();
+/// for valid in ["100-0001", "1000001"] {
+///     assert!(regex.is_match(valid), "{}", valid);
+/// }
+/// for invalid in ["100-001", "1000-001", "100 0001", "١٠٠-٠٠٠١"] {
+///     assert!(!regex.is_match(invalid), "{}", invalid);
+/// }
+/// ```
+pub fn jp() -> RegexBuilder {
+    digits(3).zero_or_one(new().literal('-')).then(digits(4))
+}
+
+/// Matches an Australian postcode of four digits.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::postal;
+/// use simple_regex::RegexBuilder;
+///
+/// let regex = RegexBuilder::new().start_of_line().then(postal::au()).end_of_line().to_regex().unwrap();
+/// for valid in ["2000", "0800", "6000"] {
+///     assert!(regex.is_match(valid), "{}", valid);
+/// }
+/// for invalid in ["200", "20000", "NSW 2000", "٢٠٠٠"] {
+///     assert!(!regex.is_match(invalid), "{}", invalid);
+/// }
+/// ```
+pub fn au() -> RegexBuilder {
+    digits(4)
+}
+
+/// Matches a Dutch postcode: four digits not starting with 0, an optional space, and
+/// two letters other than `SA`, `SD` and `SS`.
+///
+/// # Example
+///
+/// ```
+/// use simple_regex::patterns::postal;
+/// use simple_regex::RegexBuilder;
+///
+/// let regex = RegexBuilder::new().start_of_line().then(postal::nl()).end_of_line().to_regex().unwrap();
+/// for valid in ["1012 AB", "9999ZZ", "3011 SB"] {
+///     assert!(regex.is_match(valid), "{}", valid);
+/// }
+/// for invalid in ["0123 AB", "1012 SS", "1012 SA", "1012 A", "1012", "١٠١٢ AB"] {
+///     assert!(!regex.is_match(invalid), "{}", invalid);
+/// }
+/// ```
+pub fn nl() -> RegexBuilder {
+    let letters = new().any_of([
+        new().character_class("A-RT-Z").character_class("A-Z"),
+        new().literal('S').character_class("BCE-RT-Z"),
+    ]);
+    new()
+        .range_character_class('1', '9')
+        .then(digits(3))
+        .zero_or_one(new().literal(' '))
+        .then(letters)
+}